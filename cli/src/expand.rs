@@ -0,0 +1,110 @@
+use std::{path::Path, process::ExitCode};
+
+use yuuka_core::inspect::{inspect_definition, inspect_rust, Inspection};
+
+const USAGE: &str = "\
+Usage: yuuka expand <file> [root]...
+
+Print the code generated for the `derive_struct!` and `derive_enum!` calls in
+a `.rs` file, or for a `.yuuka` definition file: the types in the hidden
+`__Root` module, their `Default` impls and the `__auto_*` and `__ty_*` macros.
+Each expansion starts with the field paths of its anonymous types.
+
+With root names, only the definitions with those root types are printed.
+";
+
+pub fn run(args: &[String]) -> ExitCode {
+    let mut file = None;
+    let mut roots = vec![];
+    for arg in args {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            arg if arg.starts_with('-') => {
+                eprint!("error: unknown option `{}`\n\n{}", arg, USAGE);
+                return ExitCode::FAILURE;
+            }
+            arg if file.is_none() => file = Some(arg),
+            arg => roots.push(arg),
+        }
+    }
+    let Some(file) = file else {
+        eprint!("error: no file given\n\n{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    match expand_file(Path::new(file), &roots) {
+        Ok(text) => {
+            print!("{}", text);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn expand_file(path: &Path, roots: &[&str]) -> Result<String, String> {
+    let source =
+        std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let inspections = if path.extension().is_some_and(|ext| ext == "yuuka") {
+        inspect_definition(&source).map(|inspection| vec![inspection])
+    } else {
+        inspect_rust(&source)
+    }
+    .map_err(|err| format!("{}:{}", path.display(), err))?;
+
+    if inspections.is_empty() {
+        return Err(format!(
+            "{}: no `derive_struct!` or `derive_enum!` calls",
+            path.display()
+        ));
+    }
+    for root in roots {
+        if !inspections
+            .iter()
+            .any(|inspection| inspection.root == *root)
+        {
+            return Err(format!("{}: no definition of `{}`", path.display(), root));
+        }
+    }
+
+    Ok(inspections
+        .iter()
+        .filter(|inspection| roots.is_empty() || roots.contains(&inspection.root.as_str()))
+        .map(|inspection| header(path, inspection) + &inspection.source)
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Where the definition is, and what each anonymous type was generated for.
+fn header(path: &Path, inspection: &Inspection) -> String {
+    let mut text = format!(
+        "// `{}` from {}:{}\n",
+        inspection.root,
+        path.display(),
+        inspection.line
+    );
+    if !inspection.anonymous.is_empty() {
+        text.push_str("//\n// Anonymous types:\n");
+        let width = inspection
+            .anonymous
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .max()
+            .unwrap_or_default();
+        for (name, field) in &inspection.anonymous {
+            text.push_str(&format!(
+                "//     {:width$}  {}\n",
+                name,
+                field,
+                width = width
+            ));
+        }
+    }
+    text.push('\n');
+    text
+}
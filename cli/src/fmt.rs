@@ -0,0 +1,115 @@
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use yuuka_core::format::{format_definition, format_rust};
+
+const USAGE: &str = "\
+Usage: yuuka fmt [--check] [path]...
+
+Format the input of `derive_struct!` and `derive_enum!` in `.rs` files, and
+`.yuuka` definition files. Directories are searched recursively, skipping
+`target` and hidden directories. The default path is the current directory.
+
+Options:
+    --check    Only list the files that would change, and fail if there are any
+";
+
+pub fn run(args: &[String]) -> ExitCode {
+    let mut check = false;
+    let mut paths = vec![];
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            arg if arg.starts_with('-') => {
+                eprint!("error: unknown option `{}`\n\n{}", arg, USAGE);
+                return ExitCode::FAILURE;
+            }
+            arg => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() {
+        paths.push(PathBuf::from("."));
+    }
+
+    let mut files = vec![];
+    for path in &paths {
+        if let Err(err) = collect(path, &mut files) {
+            eprintln!("error: {}: {}", path.display(), err);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let mut failed = false;
+    for file in files {
+        match format_file(&file, check) {
+            Ok(true) if check => {
+                println!("{}", file.display());
+                failed = true;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("error: {}", err);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Every `.rs` and `.yuuka` file at a path, in a stable order.
+fn collect(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.is_dir() {
+        // Files named on the command line are formatted whatever their name.
+        std::fs::metadata(path)?;
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for entry in entries {
+        let name = entry.file_name().unwrap_or_default().to_string_lossy();
+        if entry.is_dir() {
+            if name != "target" && !name.starts_with('.') {
+                collect(&entry, files)?;
+            }
+        } else if name.ends_with(".rs") || name.ends_with(".yuuka") {
+            files.push(entry);
+        }
+    }
+    Ok(())
+}
+
+/// Format a file, returning whether it changed. With `check`, the file is
+/// left as it is.
+fn format_file(path: &Path, check: bool) -> Result<bool, String> {
+    let io_error = |err: std::io::Error| format!("{}: {}", path.display(), err);
+    let source = std::fs::read_to_string(path).map_err(io_error)?;
+    let formatted = if path.extension().is_some_and(|ext| ext == "yuuka") {
+        format_definition(&source)
+    } else {
+        format_rust(&source)
+    }
+    .map_err(|err| format!("{}:{}", path.display(), err))?;
+
+    if formatted == source {
+        return Ok(false);
+    }
+    if !check {
+        std::fs::write(path, formatted).map_err(io_error)?;
+    }
+    Ok(true)
+}
@@ -0,0 +1,32 @@
+use std::process::ExitCode;
+
+mod expand;
+mod fmt;
+
+const USAGE: &str = "\
+Usage: yuuka <command> [options]
+
+Commands:
+    fmt [--check] [path]...    Format the definitions in `.rs` and `.yuuka` files
+    expand <file> [root]...    Print the code generated for the definitions in a file
+";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        Some("fmt") => fmt::run(&args[1..]),
+        Some("expand") => expand::run(&args[1..]),
+        Some("-h" | "--help") => {
+            print!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        Some(command) => {
+            eprint!("error: unknown command `{}`\n\n{}", command, USAGE);
+            ExitCode::FAILURE
+        }
+        None => {
+            eprint!("{}", USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+fn yuuka(args: &[&str], dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_yuuka"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+/// A fresh directory with a `.rs` file holding two definitions and a
+/// `.yuuka` file.
+fn project(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("lib.rs"),
+        "derive_struct!(Config {\n    server: { host: String },\n});\n\nderive_enum!(enum Mode { Dev, Prod });\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("mode.yuuka"),
+        "pub enum Mode { Dev, Prod } = Dev\n",
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn expand() {
+    let dir = project("expand");
+    let output = yuuka(&["expand", "lib.rs"], &dir);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with(
+        "// `Config` from lib.rs:1\n//\n// Anonymous types:\n//     _Config_0_anonymous  Config.server\n\n"
+    ));
+    assert!(stdout.contains("pub struct _Config_0_anonymous"));
+    assert!(stdout.contains("macro_rules! __auto_Config"));
+    assert!(stdout.contains("\n// `Mode` from lib.rs:5\n\n"));
+
+    let output = yuuka(&["expand", "lib.rs", "Mode"], &dir);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("// `Mode` from lib.rs:5\n\n"));
+    assert!(!stdout.contains("Config"));
+
+    let output = yuuka(&["expand", "mode.yuuka"], &dir);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("// `Mode` from mode.yuuka:1\n\n"));
+    assert!(stdout.contains("impl Default for Mode"));
+}
+
+#[test]
+fn errors() {
+    let dir = project("expand_errors");
+    fs::write(dir.join("empty.rs"), "fn main() {}\n").unwrap();
+    fs::write(
+        dir.join("broken.rs"),
+        "derive_struct!(Root {\n    a: u8,\n    b\n});\n",
+    )
+    .unwrap();
+
+    for (args, message) in [
+        (
+            &["expand", "lib.rs", "Other"][..],
+            "error: lib.rs: no definition of `Other`\n",
+        ),
+        (
+            &["expand", "empty.rs"],
+            "error: empty.rs: no `derive_struct!` or `derive_enum!` calls\n",
+        ),
+        (
+            &["expand", "broken.rs"],
+            "error: broken.rs:4:1: expected `:`\n",
+        ),
+    ] {
+        let output = yuuka(args, &dir);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        assert_eq!(String::from_utf8(output.stderr).unwrap(), message);
+    }
+}
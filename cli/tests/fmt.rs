@@ -0,0 +1,98 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+const UNFORMATTED: &str = "pub Config {\n  name: String,\n  port ?: u16 = 8080\n}\n";
+const FORMATTED: &str = "pub Config {\n    name: String,\n    port?: u16 = 8080,\n}\n";
+
+fn yuuka(args: &[&str], dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_yuuka"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+/// A fresh directory with an unformatted `.rs` and `.yuuka` file, and one in
+/// `target` that is skipped.
+fn project(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::create_dir_all(dir.join("target")).unwrap();
+    fs::write(dir.join("config.yuuka"), UNFORMATTED).unwrap();
+    fs::write(dir.join("target/skipped.yuuka"), UNFORMATTED).unwrap();
+    fs::write(
+        dir.join("src/lib.rs"),
+        format!("yuuka::derive_struct!(\n{});\n", UNFORMATTED),
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn check() {
+    let dir = project("fmt_check");
+    let output = yuuka(&["fmt", "--check"], &dir);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let files = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(
+        files,
+        [
+            Path::new(".").join("config.yuuka").display().to_string(),
+            Path::new(".").join("src/lib.rs").display().to_string()
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("config.yuuka")).unwrap(),
+        UNFORMATTED
+    );
+}
+
+#[test]
+fn format() {
+    let dir = project("fmt_format");
+    let output = yuuka(&["fmt"], &dir);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(dir.join("config.yuuka")).unwrap(),
+        FORMATTED
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("src/lib.rs")).unwrap(),
+        format!("yuuka::derive_struct!({});\n", FORMATTED.trim_end())
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("target/skipped.yuuka")).unwrap(),
+        UNFORMATTED
+    );
+
+    let output = yuuka(&["fmt", "--check", "src"], &dir);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn errors() {
+    let dir = project("fmt_errors");
+    fs::write(
+        dir.join("broken.yuuka"),
+        "Root {\n    a: String = \"a,\n}\n",
+    )
+    .unwrap();
+    let output = yuuka(&["fmt", "broken.yuuka"], &dir);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: broken.yuuka:2:17: unterminated string\n"
+    );
+
+    let output = yuuka(&["fmt", "--verbose"], &dir);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("error: unknown option `--verbose`"));
+}
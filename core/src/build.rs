@@ -0,0 +1,258 @@
+//! Write the expansion of `derive_struct!` and `derive_enum!` definitions
+//! into a Rust source file from a build script.
+//!
+//! The definitions go through the same parser and templates as the macros,
+//! so the generated types, `Default` impls and `auto!` helpers are the same,
+//! but they can be read and are not expanded again on every build.
+//!
+//! ```no_run
+//! // build.rs
+//! yuuka_core::build::Codegen::new()
+//!     .derive_struct_file("schemas/config.yuuka")
+//!     .derive_enum("#[derive(PartialEq)] pub enum Mode { Dev, Prod } = Dev")
+//!     .write("models.rs")
+//!     .unwrap();
+//! ```
+//!
+//! The crate then includes the file where the macro call would have been:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/models.rs"));
+//! ```
+
+use std::{
+    fmt,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use proc_macro2::TokenStream;
+
+use crate::{
+    expand, lower_enum, lower_struct,
+    tools::{lower_text, DeriveEnum, DeriveStruct},
+    utils::pretty,
+};
+
+/// An error from [`Codegen`].
+#[derive(Debug)]
+pub enum Error {
+    /// A file could not be read or written.
+    Io(PathBuf, std::io::Error),
+    /// A definition is invalid, with every problem as `name:line:column:
+    /// message`, where the name is the path of the file it came from.
+    Definition(Vec<String>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            Error::Definition(errors) => write!(f, "{}", errors.join("\n")),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(_, err) => Some(err),
+            Error::Definition(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DefinitionKind {
+    Struct,
+    Enum,
+}
+
+#[derive(Debug, Clone)]
+enum Source {
+    Text(String),
+    /// Relative to the root of the crate being built.
+    File(PathBuf),
+}
+
+/// The definitions to write into one file, expanded in the order they were
+/// added.
+#[derive(Debug, Clone)]
+pub struct Codegen {
+    definitions: Vec<(DefinitionKind, Source)>,
+    rustfmt: bool,
+}
+
+impl Default for Codegen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Codegen {
+    pub fn new() -> Self {
+        Self {
+            definitions: vec![],
+            rustfmt: true,
+        }
+    }
+
+    /// Add what would go between the parentheses of `derive_struct!`.
+    pub fn derive_struct(mut self, text: impl Into<String>) -> Self {
+        self.definitions
+            .push((DefinitionKind::Struct, Source::Text(text.into())));
+        self
+    }
+
+    /// Add what would go between the parentheses of `derive_enum!`.
+    pub fn derive_enum(mut self, text: impl Into<String>) -> Self {
+        self.definitions
+            .push((DefinitionKind::Enum, Source::Text(text.into())));
+        self
+    }
+
+    /// Add a `derive_struct!` definition kept in a file, like
+    /// `derive_struct_file!`. The path is relative to the root of the crate
+    /// being built.
+    pub fn derive_struct_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.definitions.push((
+            DefinitionKind::Struct,
+            Source::File(path.as_ref().to_path_buf()),
+        ));
+        self
+    }
+
+    /// Add a `derive_enum!` definition kept in a file, like
+    /// `derive_enum_file!`.
+    pub fn derive_enum_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.definitions.push((
+            DefinitionKind::Enum,
+            Source::File(path.as_ref().to_path_buf()),
+        ));
+        self
+    }
+
+    /// Whether to format the source with `rustfmt` (or `$RUSTFMT`), which is
+    /// the default. Without it, or when it cannot be run, the source is still
+    /// indented, one item, field or statement per line.
+    pub fn rustfmt(mut self, rustfmt: bool) -> Self {
+        self.rustfmt = rustfmt;
+        self
+    }
+
+    /// The source of every definition, expanded and formatted.
+    pub fn generate(&self) -> Result<String, Error> {
+        let mut tokens = TokenStream::new();
+        let mut errors = vec![];
+        for (kind, source) in &self.definitions {
+            let (name, text) = match source {
+                Source::Text(text) => ("<string>".to_string(), text.clone()),
+                Source::File(path) => {
+                    let full_path = manifest_dir().join(path);
+                    let text = std::fs::read_to_string(&full_path)
+                        .map_err(|err| Error::Io(full_path, err))?;
+                    (path.display().to_string(), text)
+                }
+            };
+            let expansion = match kind {
+                DefinitionKind::Struct => lower_text(&name, &text, |tokens| {
+                    syn::parse2::<DeriveStruct>(tokens).and_then(lower_struct)
+                }),
+                DefinitionKind::Enum => lower_text(&name, &text, |tokens| {
+                    syn::parse2::<DeriveEnum>(tokens).and_then(lower_enum)
+                }),
+            };
+            match expansion {
+                Ok(expansion) => tokens.extend(expand(expansion)),
+                Err(v) => errors.extend(v),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(Error::Definition(errors));
+        }
+
+        let text = pretty(tokens);
+        let text = if self.rustfmt {
+            rustfmt(&text).unwrap_or(text)
+        } else {
+            text
+        };
+        Ok(format!(
+            "// Generated by yuuka from {}. Do not edit.\n\n{}",
+            self.sources(),
+            text
+        ))
+    }
+
+    /// Write the source into `$OUT_DIR/<file_name>`, returning its path, and
+    /// have Cargo run the build script again when a definition file changes.
+    pub fn write(&self, file_name: impl AsRef<Path>) -> Result<PathBuf, Error> {
+        let out_dir = std::env::var_os("OUT_DIR")
+            .map(PathBuf::from)
+            .ok_or_else(|| {
+                Error::Io(
+                    file_name.as_ref().to_path_buf(),
+                    std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        "`OUT_DIR` is not set, `write` is meant to be called from a build script",
+                    ),
+                )
+            })?;
+        let path = out_dir.join(file_name);
+        self.write_to(&path)?;
+        Ok(path)
+    }
+
+    /// Write the source into a file, and have Cargo run the build script
+    /// again when a definition file changes.
+    pub fn write_to(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        for (_, source) in &self.definitions {
+            if let Source::File(file) = source {
+                println!("cargo:rerun-if-changed={}", file.display());
+            }
+        }
+        let text = self.generate()?;
+        std::fs::write(path.as_ref(), text).map_err(|err| Error::Io(path.as_ref().into(), err))
+    }
+
+    fn sources(&self) -> String {
+        let names = self
+            .definitions
+            .iter()
+            .map(|(_, source)| match source {
+                Source::Text(_) => "a definition in the build script".to_string(),
+                Source::File(path) => format!("`{}`", path.display()),
+            })
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            "nothing".to_string()
+        } else {
+            names.join(", ")
+        }
+    }
+}
+
+fn manifest_dir() -> PathBuf {
+    std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+}
+
+/// Format some source with rustfmt, or `None` if it cannot be run.
+pub(crate) fn rustfmt(text: &str) -> Option<String> {
+    let rustfmt = std::env::var_os("RUSTFMT").unwrap_or_else(|| "rustfmt".into());
+    let mut child = Command::new(rustfmt)
+        .args(["--edition", "2021"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(text.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
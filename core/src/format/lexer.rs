@@ -0,0 +1,385 @@
+use super::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Kind {
+    Ident,
+    Lifetime,
+    Literal,
+    Punct,
+    Comment,
+    Open(char),
+    Close(char),
+}
+
+/// A token with its place in the source, comments included.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Token {
+    pub(super) kind: Kind,
+    pub(super) start: usize,
+    pub(super) end: usize,
+    /// Whether the token starts a line.
+    pub(super) line_before: bool,
+    /// Whether there is a blank line before the token.
+    pub(super) blank_before: bool,
+}
+
+impl Token {
+    pub(super) fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+
+    pub(super) fn is(&self, source: &str, kind: Kind, text: &str) -> bool {
+        self.kind == kind && self.text(source) == text
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Group {
+    pub(super) delimiter: char,
+    pub(super) open: Token,
+    pub(super) close: Token,
+    pub(super) children: Vec<Node>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Node {
+    Token(Token),
+    Group(Group),
+}
+
+impl Node {
+    pub(super) fn first(&self) -> &Token {
+        match self {
+            Node::Token(token) => token,
+            Node::Group(group) => &group.open,
+        }
+    }
+
+    pub(super) fn start(&self) -> usize {
+        self.first().start
+    }
+
+    pub(super) fn end(&self) -> usize {
+        match self {
+            Node::Token(token) => token.end,
+            Node::Group(group) => group.close.end,
+        }
+    }
+
+    pub(super) fn comment(&self) -> Option<&Token> {
+        match self {
+            Node::Token(token) if token.kind == Kind::Comment => Some(token),
+            _ => None,
+        }
+    }
+
+    pub(super) fn has_comment(&self) -> bool {
+        match self {
+            Node::Token(token) => token.kind == Kind::Comment,
+            Node::Group(group) => group.children.iter().any(Node::has_comment),
+        }
+    }
+
+    pub(super) fn has_brace(&self) -> bool {
+        match self {
+            Node::Token(_) => false,
+            Node::Group(group) => {
+                group.delimiter == '{' || group.children.iter().any(Node::has_brace)
+            }
+        }
+    }
+}
+
+/// Operators made of several characters. `<` and `>` are always on their own,
+/// so that generic arguments can be counted.
+const OPERATORS: &[&str] = &[
+    "...", "..=", "::", "->", "=>", "==", "!=", "&&", "||", "..", "+=", "-=", "*=", "/=", "%=",
+    "^=", "&=", "|=",
+];
+
+struct Lexer<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl Lexer<'_> {
+    fn peek(&self, n: usize) -> Option<char> {
+        self.source[self.pos..].chars().nth(n)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek(0)?;
+        self.pos += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn eat_while(&mut self, f: impl Fn(char) -> bool) {
+        while self.peek(0).is_some_and(&f) {
+            self.bump();
+        }
+    }
+
+    fn error(&self, pos: usize, message: &str) -> Error {
+        Error::at(self.source, pos, message)
+    }
+
+    /// A string after its opening quote, up to and including the closing one.
+    fn string(&mut self, start: usize) -> Result<(), Error> {
+        loop {
+            match self.bump() {
+                Some('\\') => {
+                    self.bump();
+                }
+                Some('"') => return Ok(()),
+                Some(_) => {}
+                None => return Err(self.error(start, "unterminated string")),
+            }
+        }
+    }
+
+    /// A raw string after its `r`, like `#"..."#`.
+    fn raw_string(&mut self, start: usize) -> Result<(), Error> {
+        let mut hashes = 0;
+        while self.peek(0) == Some('#') {
+            self.bump();
+            hashes += 1;
+        }
+        if self.bump() != Some('"') {
+            return Err(self.error(start, "invalid raw string"));
+        }
+        let end = format!("\"{}", "#".repeat(hashes));
+        match self.source[self.pos..].find(&end) {
+            Some(offset) => {
+                self.pos += offset + end.len();
+                Ok(())
+            }
+            None => Err(self.error(start, "unterminated raw string")),
+        }
+    }
+
+    fn suffix(&mut self) {
+        self.eat_while(is_ident_continue);
+    }
+
+    fn token(&mut self) -> Result<Kind, Error> {
+        let start = self.pos;
+        let ch = self.bump().unwrap_or_default();
+        let next = self.peek(0);
+
+        Ok(match ch {
+            '/' if next == Some('/') => {
+                self.eat_while(|ch| ch != '\n');
+                Kind::Comment
+            }
+            '/' if next == Some('*') => {
+                self.bump();
+                let mut depth = 1;
+                while depth > 0 {
+                    match self.bump() {
+                        Some('/') if self.peek(0) == Some('*') => {
+                            self.bump();
+                            depth += 1;
+                        }
+                        Some('*') if self.peek(0) == Some('/') => {
+                            self.bump();
+                            depth -= 1;
+                        }
+                        Some(_) => {}
+                        None => return Err(self.error(start, "unterminated block comment")),
+                    }
+                }
+                Kind::Comment
+            }
+            '"' => {
+                self.string(start)?;
+                self.suffix();
+                Kind::Literal
+            }
+            'r' if next == Some('"') || (next == Some('#') && self.peek(1) == Some('"')) => {
+                self.raw_string(start)?;
+                self.suffix();
+                Kind::Literal
+            }
+            'r' if next == Some('#') && self.peek(1).is_some_and(|ch| ch == '#') => {
+                self.raw_string(start)?;
+                self.suffix();
+                Kind::Literal
+            }
+            'r' if next == Some('#') && self.peek(1).is_some_and(is_ident_start) => {
+                self.bump();
+                self.eat_while(is_ident_continue);
+                Kind::Ident
+            }
+            'b' | 'c' if next == Some('"') => {
+                self.bump();
+                self.string(start)?;
+                self.suffix();
+                Kind::Literal
+            }
+            'b' | 'c' if next == Some('r') && matches!(self.peek(1), Some('"' | '#')) => {
+                self.bump();
+                self.raw_string(start)?;
+                self.suffix();
+                Kind::Literal
+            }
+            'b' if next == Some('\'') => {
+                self.bump();
+                self.char(start)?;
+                Kind::Literal
+            }
+            '\'' if next == Some('\\') || self.peek(1) == Some('\'') => {
+                self.char(start)?;
+                Kind::Literal
+            }
+            '\'' => {
+                self.eat_while(is_ident_continue);
+                Kind::Lifetime
+            }
+            '0'..='9' => {
+                self.number();
+                Kind::Literal
+            }
+            ch if is_ident_start(ch) => {
+                self.eat_while(is_ident_continue);
+                Kind::Ident
+            }
+            '(' | '[' | '{' => Kind::Open(ch),
+            ')' | ']' | '}' => Kind::Close(ch),
+            _ => {
+                let rest = &self.source[start..];
+                if let Some(operator) = OPERATORS.iter().find(|v| rest.starts_with(**v)) {
+                    self.pos = start + operator.len();
+                }
+                Kind::Punct
+            }
+        })
+    }
+
+    /// A character literal after its opening quote.
+    fn char(&mut self, start: usize) -> Result<(), Error> {
+        if self.peek(0) == Some('\\') {
+            self.bump();
+        }
+        self.bump();
+        self.eat_while(|ch| ch != '\'' && ch != '\n');
+        if self.bump() != Some('\'') {
+            return Err(self.error(start, "unterminated character literal"));
+        }
+        self.suffix();
+        Ok(())
+    }
+
+    fn number(&mut self) {
+        let hex = self.source[self.pos..].starts_with(['x', 'X']);
+        loop {
+            match self.peek(0) {
+                Some(ch) if is_ident_continue(ch) => {
+                    self.bump();
+                }
+                Some('.') if self.peek(1).is_some_and(|ch| ch.is_ascii_digit()) => {
+                    self.bump();
+                }
+                Some('+' | '-')
+                    if !hex
+                        && self.source[..self.pos].ends_with(['e', 'E'])
+                        && self.peek(1).is_some_and(|ch| ch.is_ascii_digit()) =>
+                {
+                    self.bump();
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+fn is_ident_start(ch: char) -> bool {
+    ch == '_' || ch.is_alphabetic()
+}
+
+fn is_ident_continue(ch: char) -> bool {
+    ch == '_' || ch.is_alphanumeric()
+}
+
+/// Every token of some Rust source, comments included.
+pub(super) fn lex(source: &str) -> Result<Vec<Token>, Error> {
+    let mut lexer = Lexer { source, pos: 0 };
+    let mut tokens = vec![];
+    loop {
+        let whitespace = lexer.pos;
+        lexer.eat_while(char::is_whitespace);
+        if lexer.pos == source.len() {
+            return Ok(tokens);
+        }
+        let newlines = source[whitespace..lexer.pos].matches('\n').count();
+
+        let start = lexer.pos;
+        let kind = lexer.token()?;
+        tokens.push(Token {
+            kind,
+            start,
+            end: lexer.pos,
+            line_before: tokens.is_empty() || newlines > 0,
+            blank_before: newlines > 1,
+        });
+    }
+}
+
+/// The index of the token closing the group opened at `open`.
+pub(super) fn matching(source: &str, tokens: &[Token], open: usize) -> Result<usize, Error> {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(open) {
+        match token.kind {
+            Kind::Open(_) => depth += 1,
+            Kind::Close(_) => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(Error::at(source, tokens[open].start, "unclosed delimiter"))
+}
+
+/// Nest the tokens into groups by their delimiters.
+pub(super) fn tree(source: &str, tokens: &[Token]) -> Result<Vec<Node>, Error> {
+    let mut stack: Vec<(Token, Vec<Node>)> = vec![];
+    let mut nodes = vec![];
+    for token in tokens {
+        match token.kind {
+            Kind::Open(_) => stack.push((*token, std::mem::take(&mut nodes))),
+            Kind::Close(close) => {
+                let Some((open, parent)) = stack.pop() else {
+                    return Err(Error::at(
+                        source,
+                        token.start,
+                        "unexpected closing delimiter",
+                    ));
+                };
+                let Kind::Open(delimiter) = open.kind else {
+                    unreachable!()
+                };
+                if !matches!((delimiter, close), ('(', ')') | ('[', ']') | ('{', '}')) {
+                    return Err(Error::at(
+                        source,
+                        token.start,
+                        "mismatched closing delimiter",
+                    ));
+                }
+                let children = std::mem::replace(&mut nodes, parent);
+                nodes.push(Node::Group(Group {
+                    delimiter,
+                    open,
+                    close: *token,
+                    children,
+                }));
+            }
+            _ => nodes.push(Node::Token(*token)),
+        }
+    }
+    match stack.last() {
+        Some((open, _)) => Err(Error::at(source, open.start, "unclosed delimiter")),
+        None => Ok(nodes),
+    }
+}
@@ -0,0 +1,281 @@
+//! Canonical formatting of `derive_struct!` and `derive_enum!` definitions,
+//! which rustfmt leaves as written.
+//!
+//! Each field, variant and attribute goes on its own line, nested definitions
+//! are indented by four spaces and every entry ends with a comma. Comments,
+//! blank lines between entries, attributes, default values and `?` markers
+//! are kept; types and default values are spaced like the generated code.
+//!
+//! ```
+//! let source = "derive_struct!(Root {\n  a: u8, b ?: { c: String = \"x\".to_string() } });\n";
+//! assert_eq!(
+//!     yuuka_core::format::format_rust(source).unwrap(),
+//!     r#"derive_struct!(Root {
+//!     a: u8,
+//!     b?: {
+//!         c: String = "x".to_string(),
+//!     },
+//! });
+//! "#
+//! );
+//! ```
+
+use std::{fmt, ops::Range};
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+
+mod lexer;
+mod printer;
+
+use lexer::{lex, matching, tree, Kind, Token};
+use printer::{print, MAX_WIDTH};
+
+use crate::utils::pretty_inline;
+
+/// The macros whose input is formatted.
+const MACROS: &[&str] = &["derive_struct", "derive_enum"];
+
+/// Source that could not be formatted, at a 1-based line and column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Error {
+    pub(crate) fn at(source: &str, pos: usize, message: &str) -> Self {
+        let before = &source[..pos];
+        Error {
+            line: before.matches('\n').count() + 1,
+            column: before[before.rfind('\n').map_or(0, |i| i + 1)..]
+                .chars()
+                .count()
+                + 1,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Format the input of every `derive_struct!` and `derive_enum!` call in some
+/// Rust source, leaving the rest of it as it is.
+///
+/// Calls inside `macro_rules!` bodies, which use `$` fragments, are skipped.
+pub fn format_rust(source: &str) -> Result<String, Error> {
+    let tokens = lex(source)?;
+    let mut text = String::new();
+    let mut copied = 0;
+
+    for call in find_calls(source, &tokens)? {
+        let (open, close) = (call.open, call.close);
+        let input = &tokens[open + 1..close];
+        let indent = line_indent(source, call.start);
+        text.push_str(&source[copied..tokens[open].end]);
+        let (first, last) = (input[0], input[input.len() - 1]);
+        // `derive_struct! { Root { ... } }` keeps the spaces inside its
+        // braces, and `derive_struct!(Root {` has none.
+        let gap = &source[tokens[open].end..first.start];
+        let space = if gap.is_empty() || gap.contains('\n') {
+            ""
+        } else {
+            " "
+        };
+        let block = first.kind == Kind::Comment
+            || first.is(source, Kind::Punct, "#")
+            || last.kind == Kind::Comment
+            || (tokens[open].kind == Kind::Open('{') && gap.contains('\n'));
+        if let Some(formatted) = one_line(source, &tokens, &call)? {
+            text.push_str(space);
+            text.push_str(&formatted);
+            text.push_str(space);
+        } else if !block {
+            // `derive_struct!(Root {` when the definition starts with its
+            // name.
+            let formatted = format_tokens(source, input, indent)?;
+            text.push_str(space);
+            text.push_str(formatted.trim_start());
+            text.push_str(space);
+        } else {
+            // The type on its own lines after attributes and comments.
+            let formatted = format_tokens(source, input, &format!("{}    ", indent))?;
+            text.push('\n');
+            text.push_str(&formatted);
+            text.push('\n');
+            text.push_str(indent);
+        }
+        copied = tokens[close].start;
+    }
+
+    text.push_str(&source[copied..]);
+    Ok(text)
+}
+
+/// The input of a call written on one line, spaced like the generated code,
+/// if it has no comments and still fits on its line.
+fn one_line(source: &str, tokens: &[Token], call: &Call) -> Result<Option<String>, Error> {
+    let input = &tokens[call.open + 1..call.close];
+    let (first, last) = (input[0], input[input.len() - 1]);
+    let text = &source[first.start..last.end];
+    if text.contains('\n') || input.iter().any(|token| token.kind == Kind::Comment) {
+        return Ok(None);
+    }
+    let Ok(parsed) = text.parse::<TokenStream>() else {
+        return Ok(None);
+    };
+    let formatted = pretty_inline(parsed.clone());
+
+    let line_start = source[..call.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[call.input.end..]
+        .find('\n')
+        .map_or(source.len(), |i| call.input.end + i);
+    let width = source[line_start..line_end].chars().count() - text.chars().count()
+        + formatted.chars().count();
+    if width > MAX_WIDTH {
+        return Ok(None);
+    }
+
+    let same = formatted
+        .parse::<TokenStream>()
+        .is_ok_and(|output| token_list(parsed) == token_list(output));
+    if !same {
+        return Err(Error::at(
+            source,
+            first.start,
+            "formatting would change the definition",
+        ));
+    }
+    Ok(Some(formatted))
+}
+
+/// A `derive_struct!` or `derive_enum!` call in some Rust source.
+pub(crate) struct Call {
+    pub(crate) macro_name: &'static str,
+    /// Where the name of the macro starts.
+    pub(crate) start: usize,
+    /// The input between the delimiters.
+    pub(crate) input: Range<usize>,
+    open: usize,
+    close: usize,
+}
+
+/// Every `derive_struct!` and `derive_enum!` call with some input, leaving
+/// out those in `macro_rules!` bodies, which use `$` fragments.
+pub(crate) fn calls(source: &str) -> Result<Vec<Call>, Error> {
+    find_calls(source, &lex(source)?)
+}
+
+/// Where some source stops being valid tokens, if it does.
+pub(crate) fn lex_error(source: &str) -> Option<Error> {
+    lex(source).and_then(|tokens| tree(source, &tokens)).err()
+}
+
+fn find_calls(source: &str, tokens: &[Token]) -> Result<Vec<Call>, Error> {
+    let mut calls = vec![];
+    let mut index = 0;
+    while index + 2 < tokens.len() {
+        let text = tokens[index].text(source);
+        let macro_name = match MACROS.iter().find(|name| **name == text) {
+            Some(name)
+                if tokens[index].kind == Kind::Ident
+                    && tokens[index + 1].is(source, Kind::Punct, "!")
+                    && matches!(tokens[index + 2].kind, Kind::Open(_)) =>
+            {
+                *name
+            }
+            _ => {
+                index += 1;
+                continue;
+            }
+        };
+
+        let open = index + 2;
+        let close = matching(source, tokens, open)?;
+        let input = &tokens[open + 1..close];
+        if !input.is_empty() && !input.iter().any(|token| token.text(source) == "$") {
+            calls.push(Call {
+                macro_name,
+                start: tokens[index].start,
+                input: tokens[open].end..tokens[close].start,
+                open,
+                close,
+            });
+        }
+        index = close + 1;
+    }
+    Ok(calls)
+}
+
+/// Format a definition kept in a `.yuuka` file, which holds what would go
+/// between the parentheses of `derive_struct!` or `derive_enum!`.
+pub fn format_definition(source: &str) -> Result<String, Error> {
+    let tokens = lex(source)?;
+    if tokens.is_empty() {
+        return Ok(String::new());
+    }
+    let mut text = format_tokens(source, &tokens, "")?;
+    text.push('\n');
+    Ok(text)
+}
+
+fn format_tokens(source: &str, tokens: &[Token], prefix: &str) -> Result<String, Error> {
+    let nodes = tree(source, tokens)?;
+    let formatted = print(source, &nodes, prefix);
+
+    // The formatter only moves tokens around and adds trailing commas, so
+    // anything else is a bug that must not reach the file.
+    let (first, last) = (tokens[0], tokens[tokens.len() - 1]);
+    if let Ok(input) = source[first.start..last.end].parse::<TokenStream>() {
+        let same = formatted
+            .parse::<TokenStream>()
+            .is_ok_and(|output| token_list(input) == token_list(output));
+        if !same {
+            return Err(Error::at(
+                source,
+                first.start,
+                "formatting would change the definition",
+            ));
+        }
+    }
+    Ok(formatted)
+}
+
+/// Every token, leaving out how punctuation is spaced and the commas the
+/// formatter adds, which are trailing ones and those after braces.
+fn token_list(tokens: TokenStream) -> Vec<String> {
+    let mut list = vec![];
+    let mut after_brace = false;
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                list.push(format!("{:?}", group.delimiter()));
+                list.extend(token_list(group.stream()));
+                list.push(String::new());
+                after_brace = group.delimiter() == Delimiter::Brace;
+                continue;
+            }
+            TokenTree::Punct(punct) if punct.as_char() == ',' && after_brace => {}
+            TokenTree::Punct(punct) => list.push(punct.as_char().to_string()),
+            token => list.push(token.to_string()),
+        }
+        after_brace = false;
+    }
+    if list.last().is_some_and(|token| token == ",") {
+        list.pop();
+    }
+    list
+}
+
+/// The whitespace at the start of the line holding `pos`.
+fn line_indent(source: &str, pos: usize) -> &str {
+    let start = source[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line = &source[start..pos];
+    &line[..line.len() - line.trim_start().len()]
+}
@@ -0,0 +1,438 @@
+use proc_macro2::TokenStream;
+
+use super::lexer::{Group, Kind, Node, Token};
+use crate::utils::pretty_inline;
+
+const INDENT: &str = "    ";
+
+/// The widest line before attributes are broken up, like rustfmt's
+/// `max_width`.
+pub(super) const MAX_WIDTH: usize = 100;
+
+/// The widest struct variant body kept on one line, like rustfmt's
+/// `struct_variant_width`.
+const VARIANT_WIDTH: usize = 35;
+
+/// What the entries of a braced body are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Body {
+    /// The definition itself, a root type with its attributes.
+    Root,
+    Struct,
+    Enum,
+}
+
+pub(super) struct Printer<'a> {
+    source: &'a str,
+    /// Written before every line.
+    prefix: String,
+    lines: Vec<String>,
+    /// The depth of the next line, which is only started once something is
+    /// written on it.
+    pending: Option<usize>,
+    /// Whether the current line holds nothing but its indentation.
+    at_start: bool,
+}
+
+/// The definition formatted, with `prefix` before every line and no newline
+/// at the end.
+pub(super) fn print(source: &str, nodes: &[Node], prefix: &str) -> String {
+    let mut printer = Printer {
+        source,
+        prefix: prefix.to_string(),
+        lines: vec![],
+        pending: None,
+        at_start: true,
+    };
+    printer.sequence(nodes, 0, Body::Root);
+    printer
+        .lines
+        .iter()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Printer<'_> {
+    fn new_line(&mut self, depth: usize) {
+        self.pending = Some(depth);
+    }
+
+    fn blank_line(&mut self) {
+        if self
+            .lines
+            .last()
+            .is_some_and(|line| !line.trim().is_empty())
+        {
+            self.lines.push(String::new());
+        }
+    }
+
+    fn push(&mut self, text: &str) {
+        if let Some(depth) = self.pending.take() {
+            self.lines
+                .push(format!("{}{}", self.prefix, INDENT.repeat(depth)));
+            self.at_start = true;
+        }
+        let Some(line) = self.lines.last_mut() else {
+            self.lines.push(text.to_string());
+            self.at_start = false;
+            return;
+        };
+        if !self.at_start && needs_space(line, text) {
+            line.push(' ');
+        }
+        line.push_str(text);
+        self.at_start = false;
+    }
+
+    /// A comment between entries, kept at the end of the line before when it
+    /// was written there.
+    fn comment(&mut self, token: &Token, depth: usize, first: bool) {
+        let text = token.text(self.source).trim_end();
+        if !token.line_before && self.pending.is_none() && !self.lines.is_empty() {
+            self.push(text);
+        } else {
+            if token.blank_before && !first {
+                self.blank_line();
+            }
+            self.new_line(depth);
+            self.push(text);
+        }
+        if text.starts_with("//") {
+            self.new_line(depth);
+        }
+    }
+
+    /// Some nodes on one line, spaced like the rest of the generated code, or
+    /// as written when there are comments among them.
+    fn run(&mut self, nodes: &[Node]) {
+        if !nodes.is_empty() {
+            let text = self.inline(nodes);
+            self.push(&text);
+        }
+    }
+
+    fn inline(&self, nodes: &[Node]) -> String {
+        let (Some(first), Some(last)) = (nodes.first(), nodes.last()) else {
+            return String::new();
+        };
+        let text = &self.source[first.start()..last.end()];
+        match text.parse::<TokenStream>() {
+            Ok(tokens) if !nodes.iter().any(Node::has_comment) => pretty_inline(tokens),
+            _ => text.to_string(),
+        }
+    }
+
+    /// The column the next piece of the current line starts at.
+    fn column(&self) -> usize {
+        match (self.pending, self.lines.last()) {
+            (Some(depth), _) => self.prefix.chars().count() + INDENT.len() * depth,
+            (None, Some(line)) => line.chars().count(),
+            (None, None) => 0,
+        }
+    }
+
+    /// An attribute, with the arguments of the list in it on their own lines
+    /// when it is too wide, like rustfmt lays out attributes.
+    fn attribute(&mut self, nodes: &[Node], group: &Group, depth: usize) {
+        let text = self.inline(nodes);
+        if self.column() + text.chars().count() <= MAX_WIDTH || nodes[1].has_comment() {
+            self.push(&text);
+            return;
+        }
+        self.push("#[");
+        self.list(&group.children, depth, 1);
+        self.push("]");
+    }
+
+    /// Some nodes ending with a parenthesized list, broken up one item per
+    /// line until they fit, with `closing` characters written after them.
+    fn list(&mut self, nodes: &[Node], depth: usize, closing: usize) {
+        let text = self.inline(nodes);
+        let fits = self.column() + text.chars().count() + closing <= MAX_WIDTH;
+        let Some(Node::Group(group)) = nodes.last() else {
+            self.push(&text);
+            return;
+        };
+        if fits || group.delimiter != '(' || group.children.is_empty() {
+            self.push(&text);
+            return;
+        }
+
+        self.run(&nodes[..nodes.len() - 1]);
+        self.push("(");
+        let items = self.items(&group.children);
+        // `a(b(` with a single list in a list.
+        if let [item] = items.as_slice() {
+            if matches!(item.last(), Some(Node::Group(group)) if group.delimiter == '(') {
+                self.list(item, depth, closing + 1);
+                self.push(")");
+                return;
+            }
+        }
+        for (index, item) in items.iter().enumerate() {
+            self.new_line(depth + 1);
+            let last = index + 1 == items.len();
+            self.list(item, depth + 1, if last { 0 } else { 1 });
+            if !last {
+                self.push(",");
+            }
+        }
+        self.new_line(depth);
+        self.push(")");
+    }
+
+    /// The items of a list separated by commas, leaving out a trailing one.
+    fn items<'n>(&self, nodes: &'n [Node]) -> Vec<&'n [Node]> {
+        let mut items = vec![];
+        let mut rest = nodes;
+        while !rest.is_empty() {
+            let (end, next) = self.entry_end(rest);
+            items.push(&rest[..end]);
+            rest = &rest[next..];
+        }
+        items
+    }
+
+    /// The entries of a body, each on its own lines and followed by a comma
+    /// unless they are the root of the definition.
+    fn sequence(&mut self, nodes: &[Node], depth: usize, body: Body) {
+        let mut rest = nodes;
+        let mut first = true;
+        while let Some(node) = rest.first() {
+            if let Some(token) = node.comment() {
+                self.comment(token, depth, first);
+                first = false;
+                rest = &rest[1..];
+                continue;
+            }
+
+            // Attributes go on their own lines.
+            if let (Node::Token(hash), Some(Node::Group(group))) = (node, rest.get(1)) {
+                if hash.is(self.source, Kind::Punct, "#") && group.delimiter == '[' {
+                    if hash.blank_before && !first {
+                        self.blank_line();
+                    }
+                    self.new_line(depth);
+                    self.attribute(&rest[..2], group, depth);
+                    self.new_line(depth);
+                    first = false;
+                    rest = &rest[2..];
+                    continue;
+                }
+            }
+
+            let (end, next) = self.entry_end(rest);
+            let core = rest[..end]
+                .iter()
+                .rposition(|node| node.comment().is_none())
+                .map_or(0, |index| index + 1);
+
+            if node.first().blank_before && !first {
+                self.blank_line();
+            }
+            self.new_line(depth);
+            self.definition(&rest[..core], depth, body == Body::Enum);
+            // `/* ... */` comments stay before the comma, while `//` ones
+            // and everything after them go after it.
+            let trailing = &rest[core..end];
+            let before = trailing
+                .iter()
+                .position(|node| {
+                    node.comment()
+                        .is_some_and(|token| token.text(self.source).starts_with("//"))
+                })
+                .unwrap_or(trailing.len());
+            for node in &trailing[..before] {
+                if let Some(token) = node.comment() {
+                    self.comment(token, depth, false);
+                }
+            }
+            if body != Body::Root {
+                self.push(",");
+            }
+            for node in &trailing[before..] {
+                if let Some(token) = node.comment() {
+                    self.comment(token, depth, false);
+                }
+            }
+            first = false;
+            rest = &rest[next..];
+        }
+    }
+
+    /// Where the entry at the start of `nodes` ends, and where the next one
+    /// starts after the comma between them.
+    ///
+    /// The comma is optional after a nested definition without a default
+    /// value, so the entry also ends there.
+    fn entry_end(&self, nodes: &[Node]) -> (usize, usize) {
+        let mut angle = 0;
+        let mut expression = false;
+        let mut prev = "";
+        for (index, node) in nodes.iter().enumerate() {
+            let Node::Token(token) = node else {
+                let next = nodes[index + 1..]
+                    .iter()
+                    .find(|node| node.comment().is_none());
+                let continued = next.is_some_and(
+                    |node| matches!(node, Node::Token(token) if token.kind == Kind::Punct),
+                );
+                if !expression
+                    && matches!(node, Node::Group(group) if group.delimiter == '{')
+                    && !continued
+                {
+                    // Block comments on the same line still belong to it.
+                    let end = index
+                        + 1
+                        + nodes[index + 1..]
+                            .iter()
+                            .take_while(|node| {
+                                node.comment().is_some_and(|token| {
+                                    !token.line_before && !token.text(self.source).starts_with("//")
+                                })
+                            })
+                            .count();
+                    return (end, end);
+                }
+                prev = "";
+                continue;
+            };
+            let text = token.text(self.source);
+            if token.kind == Kind::Punct {
+                match text {
+                    "," if angle == 0 => return (index, index + 1),
+                    "=" if !expression => {
+                        expression = true;
+                        angle = 0;
+                    }
+                    // Generic arguments, which in a default value follow `::`.
+                    "<" if !expression || prev == "::" => angle += 1,
+                    ">" if angle > 0 => angle -= 1,
+                    _ => {}
+                }
+            }
+            if token.kind != Kind::Comment {
+                prev = text;
+            }
+        }
+        (nodes.len(), nodes.len())
+    }
+
+    /// The types of an entry, where braces hold nested definitions. Anything
+    /// after `=` is a default value and stays on the line.
+    fn definition(&mut self, nodes: &[Node], depth: usize, variant: bool) {
+        let mut start = 0;
+        for (index, node) in nodes.iter().enumerate() {
+            match node {
+                Node::Token(token) if token.kind == Kind::Comment => {
+                    self.run(&nodes[start..index]);
+                    self.push(token.text(self.source).trim_end());
+                    if token.text(self.source).starts_with("//") {
+                        self.new_line(depth + 1);
+                    }
+                    start = index + 1;
+                }
+                Node::Token(token) if token.is(self.source, Kind::Punct, "=") => break,
+                Node::Group(group) if group.delimiter == '{' => {
+                    self.run(&nodes[start..index]);
+                    let body = if self.after_enum(&nodes[..index]) {
+                        Body::Enum
+                    } else {
+                        Body::Struct
+                    };
+                    self.braces(group, depth, body, variant && body == Body::Struct);
+                    start = index + 1;
+                }
+                Node::Group(group) if node.has_brace() => {
+                    self.run(&nodes[start..index]);
+                    let (open, close) = if group.delimiter == '(' {
+                        ("(", ")")
+                    } else {
+                        ("[", "]")
+                    };
+                    self.push(open);
+                    let items = self.items(&group.children);
+                    if items.len() > 1 && !group.children.iter().any(Node::has_comment) {
+                        // `Variant(Name { ... }, u8)`, one element per line.
+                        for item in items {
+                            self.new_line(depth + 1);
+                            self.definition(item, depth + 1, false);
+                            self.push(",");
+                        }
+                        self.new_line(depth);
+                    } else {
+                        self.definition(&group.children, depth, false);
+                    }
+                    self.push(close);
+                    start = index + 1;
+                }
+                _ => {}
+            }
+        }
+        self.run(&nodes[start..]);
+    }
+
+    /// Whether the braces after these nodes hold the variants of an enum,
+    /// which is after `enum` or `enum Name`.
+    fn after_enum(&self, nodes: &[Node]) -> bool {
+        let mut words = nodes.iter().rev().filter(|node| node.comment().is_none());
+        let is = |node: Option<&Node>, text: Option<&str>| match node {
+            Some(Node::Token(token)) if token.kind == Kind::Ident => {
+                text.map_or(true, |text| token.text(self.source) == text)
+            }
+            _ => false,
+        };
+        let last = words.next();
+        is(last, Some("enum")) || (is(last, None) && is(words.next(), Some("enum")))
+    }
+
+    fn braces(&mut self, group: &Group, depth: usize, body: Body, variant: bool) {
+        if group.children.is_empty() {
+            self.push("{}");
+            return;
+        }
+        if variant {
+            if let Some(text) = self.one_line(group) {
+                self.push(&format!("{{ {} }}", text));
+                return;
+            }
+        }
+
+        self.push("{");
+        self.sequence(&group.children, depth + 1, body);
+        self.new_line(depth);
+        self.push("}");
+    }
+
+    /// The fields of a struct variant on one line, if they are plain and
+    /// short enough.
+    fn one_line(&self, group: &Group) -> Option<String> {
+        let plain = group.children.iter().all(|node| {
+            !node.has_comment()
+                && !node.has_brace()
+                && !matches!(node, Node::Token(token) if token.is(self.source, Kind::Punct, "#"))
+        });
+        if !plain {
+            return None;
+        }
+
+        let mut fields = vec![];
+        let mut rest = group.children.as_slice();
+        while !rest.is_empty() {
+            let (end, next) = self.entry_end(rest);
+            fields.push(self.inline(&rest[..end]));
+            rest = &rest[next..];
+        }
+        let text = fields.join(", ");
+        (text.chars().count() <= VARIANT_WIDTH).then_some(text)
+    }
+}
+
+/// Whether a space goes between the end of a line and the next piece of it.
+fn needs_space(line: &str, next: &str) -> bool {
+    !(line.ends_with(['(', '['])
+        || next.starts_with([')', ']', ',', '('])
+        || line.trim().is_empty())
+}
@@ -0,0 +1,200 @@
+//! The expansion of `derive_struct!` and `derive_enum!` definitions as
+//! readable source, for the `yuuka expand` command.
+//!
+//! Compiler errors in the generated code point into the hidden `__Root`
+//! module. An [`Inspection`] shows what is in it: the flattened types, their
+//! `Default` impls and the `__auto_*` and `__ty_*` macros, along with the
+//! field each anonymous type was generated for.
+//!
+//! ```
+//! let inspection = yuuka_core::inspect::inspect_definition("Root { a: { b: u8 } }").unwrap();
+//! assert_eq!(inspection.root, "Root");
+//! assert_eq!(
+//!     inspection.anonymous,
+//!     [("_Root_0_anonymous".to_string(), "Root.a".to_string())]
+//! );
+//! assert!(inspection.source.contains("pub struct _Root_0_anonymous"));
+//! ```
+
+use std::collections::HashMap;
+
+use proc_macro2::{TokenStream, TokenTree};
+
+use crate::{
+    build::rustfmt,
+    expand,
+    format::calls,
+    ir::{Definition, Field, Type, TypeKind, VariantFields},
+    lower_enum, lower_struct,
+    tools::{DeriveEnum, DeriveStruct},
+    utils::pretty,
+};
+
+pub use crate::format::Error;
+
+/// One definition, expanded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inspection {
+    /// The name of the root type.
+    pub root: String,
+    /// The 1-based line of the macro call, or the first line of a `.yuuka`
+    /// file that is not blank.
+    pub line: usize,
+    /// Every anonymous type, with the path of the field or variant it was
+    /// generated for, like `Config.server.tls` or `Command::Move.0`.
+    pub anonymous: Vec<(String, String)>,
+    /// The expansion, formatted with `rustfmt` (or `$RUSTFMT`) when it can be
+    /// run, and indented one item per line otherwise.
+    pub source: String,
+}
+
+/// Expand every `derive_struct!` and `derive_enum!` call in some Rust
+/// source, in the order they appear.
+pub fn inspect_rust(source: &str) -> Result<Vec<Inspection>, Error> {
+    calls(source)?
+        .into_iter()
+        .map(|call| {
+            let line = Error::at(source, call.start, "").line;
+            let at = Error::at(source, call.input.start, "");
+            inspect(
+                &source[call.input],
+                call.macro_name == "derive_enum",
+                line,
+                (at.line, at.column),
+            )
+        })
+        .collect()
+}
+
+/// Expand a definition kept in a `.yuuka` file, which holds what would go
+/// between the parentheses of `derive_struct!` or `derive_enum!`. It is an
+/// enum when `enum` follows its attributes and visibility.
+pub fn inspect_definition(source: &str) -> Result<Inspection, Error> {
+    let start = source.len() - source.trim_start().len();
+    let at = Error::at(source, start, "");
+    let is_enum = source.parse::<TokenStream>().is_ok_and(starts_with_enum);
+    inspect(&source[start..], is_enum, at.line, (at.line, at.column))
+}
+
+/// Whether the first word after the attributes and visibility is `enum`.
+fn starts_with_enum(tokens: TokenStream) -> bool {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            // `#[...]`
+            TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                tokens.next();
+            }
+            TokenTree::Ident(ident) if ident == "pub" => {
+                // `pub(crate)`
+                if matches!(tokens.peek(), Some(TokenTree::Group(_))) {
+                    tokens.next();
+                }
+            }
+            TokenTree::Ident(ident) => return ident == "enum",
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Expand the input of a macro that starts at `start`, a 1-based line and
+/// column of the file, so errors point into the file.
+fn inspect(
+    text: &str,
+    is_enum: bool,
+    line: usize,
+    start: (usize, usize),
+) -> Result<Inspection, Error> {
+    let error = |err: syn::Error| {
+        let at = err.span().start();
+        Error {
+            line: start.0 + at.line - 1,
+            column: if at.line == 1 {
+                start.1 + at.column
+            } else {
+                at.column + 1
+            },
+            message: err.to_string(),
+        }
+    };
+
+    let tokens = text
+        .parse::<TokenStream>()
+        .map_err(|err| error(syn::Error::new(err.span(), err)))?;
+    let expansion = if is_enum {
+        syn::parse2::<DeriveEnum>(tokens).and_then(lower_enum)
+    } else {
+        syn::parse2::<DeriveStruct>(tokens).and_then(lower_struct)
+    }
+    .map_err(error)?;
+
+    let definition = Definition::from_expansion(&expansion);
+    let text = pretty(expand(expansion));
+    Ok(Inspection {
+        anonymous: anonymous_paths(&definition),
+        root: definition.root,
+        line,
+        source: rustfmt(&text).unwrap_or(text),
+    })
+}
+
+/// Every anonymous type and where it is, walking the definition from its
+/// root.
+fn anonymous_paths(definition: &Definition) -> Vec<(String, String)> {
+    let structs = definition
+        .structs
+        .iter()
+        .map(|v| (v.name.as_str(), v))
+        .collect::<HashMap<_, _>>();
+    let enums = definition
+        .enums
+        .iter()
+        .map(|v| (v.name.as_str(), v))
+        .collect::<HashMap<_, _>>();
+
+    let mut paths = vec![];
+    let mut stack = vec![(definition.root.clone(), definition.root.clone())];
+    while let Some((name, path)) = stack.pop() {
+        let mut nested = vec![];
+        if let Some(item) = structs.get(name.as_str()) {
+            if item.anonymous {
+                paths.push((name.clone(), path.clone()));
+            }
+            nested.extend(field_types(&item.fields, &path));
+        } else if let Some(item) = enums.get(name.as_str()) {
+            if item.anonymous {
+                paths.push((name.clone(), path.clone()));
+            }
+            for variant in &item.variants {
+                let path = format!("{}::{}", path, variant.name);
+                match &variant.fields {
+                    VariantFields::Unit => {}
+                    VariantFields::Tuple(types) => {
+                        for (index, ty) in types.iter().enumerate() {
+                            nested.push((ty, format!("{}.{}", path, index)));
+                        }
+                    }
+                    VariantFields::Struct(fields) => nested.extend(field_types(fields, &path)),
+                }
+            }
+        }
+
+        // Reversed, so the types come out in the order they are written.
+        stack.extend(
+            nested
+                .into_iter()
+                .rev()
+                .filter(|(ty, _)| ty.kind != TypeKind::Static)
+                .map(|(ty, path)| (ty.inner.clone(), path)),
+        );
+    }
+    paths
+}
+
+fn field_types<'a>(fields: &'a [Field], path: &str) -> Vec<(&'a Type, String)> {
+    fields
+        .iter()
+        .map(|field| (&field.ty, format!("{}.{}", path, field.name)))
+        .collect()
+}
@@ -0,0 +1,441 @@
+//! The intermediate representation between the DSL and the generated code.
+//!
+//! A [`Definition`] is what one `derive_struct!` or `derive_enum!` invocation
+//! becomes once inline types are flattened and named: the list of structs and
+//! enums that will be generated, with every type, default value and attribute
+//! written out as Rust source. It can be serialized with serde, so tools can
+//! read a definition without parsing the DSL themselves, and turned back into
+//! the expansion of the macro with [`Definition::expand`].
+//!
+//! ```
+//! use yuuka_core::ir::{Definition, DefaultValue};
+//!
+//! let definition = Definition::from_struct(
+//!     r#"Config { name: String = "app".to_string(), log?: { level: u8 } }"#
+//!         .parse()
+//!         .unwrap(),
+//! )
+//! .unwrap();
+//!
+//! let config = definition.structs.iter().find(|v| v.name == "Config").unwrap();
+//! assert_eq!(config.fields[0].ty.rust, "String");
+//! assert_eq!(
+//!     config.fields[0].default,
+//!     DefaultValue::Single(r#""app".to_string()"#.to_string())
+//! );
+//! assert_eq!(config.fields[1].ty.rust, "Option<_Config_0_anonymous>");
+//!
+//! let json = serde_json::to_string(&definition).unwrap();
+//! let definition: Definition = serde_json::from_str(&json).unwrap();
+//! let tokens = definition.expand().unwrap();
+//! ```
+
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use serde::{Deserialize, Serialize};
+use syn::{Expr, Ident, TypePath};
+
+use crate::{
+    lower_enum, lower_struct,
+    tools::{
+        DefaultValue as DefaultValueTokens, DeriveAutoMacrosVisibility, DeriveEnum, DeriveStruct,
+        EnumValueFlatten, ExtraMacrosFlatten, ExtraTypeWrapper, StructMembersFlatten, TypeFlatten,
+        TypeFlattenKind,
+    },
+    utils::pretty_inline,
+    Expansion,
+};
+
+pub use crate::tools::DeriveFeatures;
+
+/// Every type generated by one `derive_struct!` or `derive_enum!`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Definition {
+    /// The name of the root type. The types are generated in a module named
+    /// `__{root}` and re-exported next to the macro call.
+    pub root: String,
+    /// Whether the module and re-export are `pub`, rather than `pub(crate)`.
+    pub public: bool,
+    /// Whether the `auto!` and `ty!` helper macros are exported from the
+    /// crate, set with `#[macro_export]`.
+    pub macros_public: bool,
+    /// The structs, nested types before the types that use them and the root
+    /// last when it is a struct.
+    pub structs: Vec<Struct>,
+    /// The enums, in the same order as `structs`.
+    pub enums: Vec<Enum>,
+}
+
+/// A generated struct.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Struct {
+    pub name: String,
+    /// Whether the struct was declared inline without a name, so that `name`
+    /// was generated.
+    pub anonymous: bool,
+    /// The paths in `#[derive(...)]`, besides `Debug`, `Clone` and `Default`.
+    pub derives: Vec<String>,
+    /// The inside of every other attribute, like `serde(rename_all = "camelCase")`.
+    pub attributes: Vec<String>,
+    pub features: DeriveFeatures,
+    pub fields: Vec<Field>,
+}
+
+/// A generated enum.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Enum {
+    pub name: String,
+    /// Whether the enum was declared inline without a name.
+    pub anonymous: bool,
+    /// The paths in `#[derive(...)]`, besides `Debug` and `Clone`.
+    pub derives: Vec<String>,
+    /// The inside of every other attribute.
+    pub attributes: Vec<String>,
+    pub features: DeriveFeatures,
+    pub variants: Vec<Variant>,
+    /// The expression returned by `Default::default`, like `Self::Quit`, or
+    /// `None` when the enum has no `Default` impl.
+    pub default: Option<String>,
+}
+
+/// A named field of a struct or of a struct variant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Field {
+    pub name: String,
+    pub ty: Type,
+    /// The value given to the field in the `Default` impl.
+    pub default: DefaultValue,
+    /// The inside of every attribute on the field.
+    pub attributes: Vec<String>,
+}
+
+/// The type of a field or of a tuple variant element.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Type {
+    /// The full type in the generated code, like `Option<Vec<_Root_0_anonymous>>`.
+    pub rust: String,
+    /// The type inside the `?` and `[...]` wrappers, which is the generated
+    /// name for inline types.
+    pub inner: String,
+    pub kind: TypeKind,
+    pub wrapper: Wrapper,
+    /// The number set with `#[yuuka(proto = 3)]`.
+    pub proto_number: Option<u32>,
+}
+
+/// Where the type inside the wrappers comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeKind {
+    /// A type written out in the DSL.
+    Static,
+    /// An inline struct, generated as one of [`Definition::structs`].
+    InlineStruct,
+    /// An inline enum, generated as one of [`Definition::enums`].
+    InlineEnum,
+}
+
+/// The wrapper added by `?` and `[...]`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Wrapper {
+    Plain,
+    /// `[T]`, a `Vec<T>`.
+    Vec,
+    /// `name?: T`, an `Option<T>`.
+    Option,
+    /// `name?: [T]`, an `Option<Vec<T>>`.
+    OptionVec,
+}
+
+/// The default value of a field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultValue {
+    /// `Default::default()`.
+    None,
+    /// `= expr`.
+    Single(String),
+    /// The items of a `vec![...]` on a `[...]` field.
+    Array(Vec<String>),
+}
+
+/// A generated enum variant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Variant {
+    pub name: String,
+    pub fields: VariantFields,
+    /// The number set with `#[yuuka(proto = 3)]`.
+    pub proto_number: Option<u32>,
+    /// The inside of every attribute on the variant.
+    pub attributes: Vec<String>,
+}
+
+/// The fields of an enum variant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VariantFields {
+    Unit,
+    Tuple(Vec<Type>),
+    Struct(Vec<Field>),
+}
+
+impl Definition {
+    /// Parse and flatten what would go between the parentheses of
+    /// `derive_struct!`.
+    pub fn from_struct(input: TokenStream) -> syn::Result<Self> {
+        let expansion = syn::parse2::<DeriveStruct>(input).and_then(lower_struct)?;
+        Ok(Self::from_expansion(&expansion))
+    }
+
+    /// Parse and flatten what would go between the parentheses of
+    /// `derive_enum!`.
+    pub fn from_enum(input: TokenStream) -> syn::Result<Self> {
+        let expansion = syn::parse2::<DeriveEnum>(input).and_then(lower_enum)?;
+        Ok(Self::from_expansion(&expansion))
+    }
+
+    /// The code the macro generates for this definition: the module with the
+    /// types, their impls and helper macros, and its re-export.
+    ///
+    /// Fails when a name, type, expression or attribute is not valid Rust.
+    pub fn expand(&self) -> syn::Result<TokenStream> {
+        Ok(crate::expand(self.to_expansion()?))
+    }
+
+    pub(crate) fn from_expansion(expansion: &Expansion) -> Self {
+        Self {
+            root: expansion
+                .mod_ident
+                .to_string()
+                .strip_prefix("__")
+                .unwrap_or_default()
+                .to_string(),
+            public: expansion.is_public,
+            macros_public: expansion.macro_visibility == DeriveAutoMacrosVisibility::Public,
+            structs: expansion
+                .structs
+                .iter()
+                .map(|(ident, fields, extra_macros)| Struct {
+                    name: ident.to_string(),
+                    anonymous: extra_macros.anonymous,
+                    derives: extra_macros
+                        .derive_macros
+                        .iter()
+                        .map(pretty_inline)
+                        .collect(),
+                    attributes: extra_macros.attr_macros.iter().map(pretty_inline).collect(),
+                    features: extra_macros.features,
+                    fields: fields_from_flatten(fields),
+                })
+                .collect(),
+            enums: expansion
+                .enums
+                .iter()
+                .map(|(ident, variants, default_value, extra_macros)| Enum {
+                    name: ident.to_string(),
+                    anonymous: extra_macros.anonymous,
+                    derives: extra_macros
+                        .derive_macros
+                        .iter()
+                        .map(pretty_inline)
+                        .collect(),
+                    attributes: extra_macros.attr_macros.iter().map(pretty_inline).collect(),
+                    features: extra_macros.features,
+                    variants: variants
+                        .iter()
+                        .map(|(ident, value, proto_number, attributes)| Variant {
+                            name: ident.to_string(),
+                            fields: match value {
+                                EnumValueFlatten::Empty => VariantFields::Unit,
+                                EnumValueFlatten::Tuple(v) => {
+                                    VariantFields::Tuple(v.iter().map(type_from_flatten).collect())
+                                }
+                                EnumValueFlatten::Struct(v) => {
+                                    VariantFields::Struct(fields_from_flatten(v))
+                                }
+                            },
+                            proto_number: *proto_number,
+                            attributes: attributes.iter().map(pretty_inline).collect(),
+                        })
+                        .collect(),
+                    default: match DefaultValue::from(default_value) {
+                        DefaultValue::Single(v) => Some(v),
+                        _ => None,
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    fn to_expansion(&self) -> syn::Result<Expansion> {
+        // Inline enums with a default variant implement `Default`.
+        let enum_defaults = self
+            .enums
+            .iter()
+            .filter(|v| v.default.is_some())
+            .map(|v| v.name.as_str())
+            .collect::<HashSet<_>>();
+
+        let mut structs = vec![];
+        for v in &self.structs {
+            structs.push((
+                parse::<Ident>(&v.name)?,
+                fields_to_flatten(&v.fields, &enum_defaults)?,
+                extra_macros_to_flatten(&v.derives, &v.attributes, v.features, v.anonymous)?,
+            ));
+        }
+
+        let mut enums = vec![];
+        for v in &self.enums {
+            let mut variants = vec![];
+            for variant in &v.variants {
+                let value = match &variant.fields {
+                    VariantFields::Unit => EnumValueFlatten::Empty,
+                    VariantFields::Tuple(v) => EnumValueFlatten::Tuple(
+                        v.iter()
+                            .map(|ty| type_to_flatten(ty, &enum_defaults))
+                            .collect::<syn::Result<_>>()?,
+                    ),
+                    VariantFields::Struct(v) => {
+                        EnumValueFlatten::Struct(fields_to_flatten(v, &enum_defaults)?)
+                    }
+                };
+                variants.push((
+                    parse::<Ident>(&variant.name)?,
+                    value,
+                    variant.proto_number,
+                    parse_all::<TokenStream>(&variant.attributes)?,
+                ));
+            }
+            enums.push((
+                parse::<Ident>(&v.name)?,
+                variants,
+                match &v.default {
+                    Some(v) => DefaultValueTokens::Single(Box::new(parse::<Expr>(v)?)),
+                    None => DefaultValueTokens::None,
+                },
+                extra_macros_to_flatten(&v.derives, &v.attributes, v.features, v.anonymous)?,
+            ));
+        }
+
+        Ok(Expansion {
+            is_public: self.public,
+            macro_visibility: if self.macros_public {
+                DeriveAutoMacrosVisibility::Public
+            } else {
+                DeriveAutoMacrosVisibility::PublicOnCrate
+            },
+            mod_ident: parse::<Ident>(&format!("__{}", self.root))?,
+            structs,
+            enums,
+        })
+    }
+}
+
+impl From<&DefaultValueTokens> for DefaultValue {
+    fn from(value: &DefaultValueTokens) -> Self {
+        match value {
+            DefaultValueTokens::None => DefaultValue::None,
+            DefaultValueTokens::Single(v) => DefaultValue::Single(pretty_inline(v)),
+            DefaultValueTokens::Array(v) => {
+                DefaultValue::Array(v.iter().map(pretty_inline).collect())
+            }
+        }
+    }
+}
+
+fn parse<T: syn::parse::Parse>(text: &str) -> syn::Result<T> {
+    syn::parse_str(text).map_err(|err| syn::Error::new(err.span(), format!("`{}`: {}", text, err)))
+}
+
+fn parse_all<T: syn::parse::Parse>(texts: &[String]) -> syn::Result<Vec<T>> {
+    texts.iter().map(|text| parse(text)).collect()
+}
+
+fn fields_from_flatten(fields: &StructMembersFlatten) -> Vec<Field> {
+    fields
+        .iter()
+        .map(|(ident, ty, default_value, attributes)| Field {
+            name: ident.to_string(),
+            ty: type_from_flatten(ty),
+            default: default_value.into(),
+            attributes: attributes.iter().map(pretty_inline).collect(),
+        })
+        .collect()
+}
+
+fn fields_to_flatten(
+    fields: &[Field],
+    enum_defaults: &HashSet<&str>,
+) -> syn::Result<StructMembersFlatten> {
+    fields
+        .iter()
+        .map(|field| {
+            Ok((
+                parse::<Ident>(&field.name)?,
+                type_to_flatten(&field.ty, enum_defaults)?,
+                match &field.default {
+                    DefaultValue::None => DefaultValueTokens::None,
+                    DefaultValue::Single(v) => DefaultValueTokens::Single(Box::new(parse(v)?)),
+                    DefaultValue::Array(v) => DefaultValueTokens::Array(parse_all(v)?),
+                },
+                parse_all::<TokenStream>(&field.attributes)?,
+            ))
+        })
+        .collect()
+}
+
+fn type_from_flatten(ty: &TypeFlatten) -> Type {
+    Type {
+        rust: pretty_inline(&ty.ty),
+        inner: pretty_inline(&ty.inner),
+        kind: match ty.kind {
+            TypeFlattenKind::Static => TypeKind::Static,
+            TypeFlattenKind::InlineStruct => TypeKind::InlineStruct,
+            TypeFlattenKind::InlineEnum => TypeKind::InlineEnum,
+        },
+        wrapper: match ty.wrapper {
+            ExtraTypeWrapper::Default => Wrapper::Plain,
+            ExtraTypeWrapper::Vec => Wrapper::Vec,
+            ExtraTypeWrapper::Option => Wrapper::Option,
+            ExtraTypeWrapper::OptionVec => Wrapper::OptionVec,
+        },
+        proto_number: ty.proto_number,
+    }
+}
+
+fn type_to_flatten(ty: &Type, enum_defaults: &HashSet<&str>) -> syn::Result<TypeFlatten> {
+    Ok(TypeFlatten {
+        ty: parse::<TypePath>(&ty.rust)?,
+        inner: parse::<TypePath>(&ty.inner)?,
+        kind: match ty.kind {
+            TypeKind::Static => TypeFlattenKind::Static,
+            TypeKind::InlineStruct => TypeFlattenKind::InlineStruct,
+            TypeKind::InlineEnum => TypeFlattenKind::InlineEnum,
+        },
+        wrapper: match ty.wrapper {
+            Wrapper::Plain => ExtraTypeWrapper::Default,
+            Wrapper::Vec => ExtraTypeWrapper::Vec,
+            Wrapper::Option => ExtraTypeWrapper::Option,
+            Wrapper::OptionVec => ExtraTypeWrapper::OptionVec,
+        },
+        proto_number: ty.proto_number,
+        enum_default: ty.kind == TypeKind::InlineEnum && enum_defaults.contains(ty.inner.as_str()),
+    })
+}
+
+fn extra_macros_to_flatten(
+    derives: &[String],
+    attributes: &[String],
+    features: DeriveFeatures,
+    anonymous: bool,
+) -> syn::Result<ExtraMacrosFlatten> {
+    Ok(ExtraMacrosFlatten {
+        derive_macros: parse_all(derives)?,
+        attr_macros: parse_all(attributes)?,
+        features,
+        anonymous,
+    })
+}
@@ -0,0 +1,412 @@
+//! The parser, flattening and code generation behind the `yuuka` macros.
+//!
+//! Each macro of `yuuka-macros` hands its input to the function of the same
+//! name here. [`ir`] exposes the flattened types in between, and [`build`]
+//! writes the same expansion into a file from a build script instead.
+//! [`format`] lays out definitions for the `yuuka fmt` command, and [`inspect`]
+//! shows their expansion for `yuuka expand`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+/// Parse the input of a macro, or return the parse error as the expansion.
+macro_rules! parse_input {
+    ($input:ident as $ty:ty) => {
+        match syn::parse2::<$ty>($input) {
+            Ok(v) => v,
+            Err(err) => return err.to_compile_error(),
+        }
+    };
+}
+
+pub mod build;
+pub mod format;
+pub mod inspect;
+pub mod ir;
+mod template;
+mod tools;
+mod utils;
+
+use template::{
+    generate_enums_auto_macros, generate_enums_quote, generate_enums_ty_macros,
+    generate_structs_auto_macros, generate_structs_quote, generate_structs_ty_macros,
+};
+use tools::{
+    auto_macros::AutoMacrosType, AutoMacros, DeriveAutoMacrosVisibility, DeriveBox, DeriveEnum,
+    DeriveFile, DeriveStruct, DeriveStructFromJson, DeriveVisibility, EnumsFlatten, ExtraMacros,
+    StructName, StructsFlatten, TyMacros,
+};
+use utils::{
+    check_unique_names, flatten, import_json_schema, infer_struct_items, validate, FlattenContext,
+};
+
+/// The expansion of `derive_struct!`.
+pub fn derive_struct(input: TokenStream) -> TokenStream {
+    let input = parse_input!(input as DeriveStruct);
+
+    expand_struct(input)
+}
+
+/// The expansion of `derive_struct_from_json!`.
+pub fn derive_struct_from_json(input: TokenStream) -> TokenStream {
+    let input = parse_input!(input as DeriveStructFromJson);
+
+    expand_struct_from_file(input.to_derive_struct(infer_struct_items))
+}
+
+/// The expansion of `derive_struct_from_json_schema!`.
+pub fn derive_struct_from_json_schema(input: TokenStream) -> TokenStream {
+    let input = parse_input!(input as DeriveStructFromJson);
+
+    expand_struct_from_file(input.to_derive_struct(import_json_schema))
+}
+
+fn expand_struct_from_file(input: syn::Result<(DeriveStruct, String)>) -> TokenStream {
+    match input {
+        Ok((input, path)) => {
+            let expanded = expand_struct(input);
+            quote! {
+                // Rebuild when the file changes.
+                const _: &str = include_str!(#path);
+
+                #expanded
+            }
+        }
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+/// The types generated by one macro invocation, flattened and checked.
+struct Expansion {
+    is_public: bool,
+    macro_visibility: DeriveAutoMacrosVisibility,
+    mod_ident: Ident,
+    structs: StructsFlatten,
+    enums: EnumsFlatten,
+}
+
+fn lower_struct(input: DeriveStruct) -> syn::Result<Expansion> {
+    let input = input.hoist_attr_macros();
+    let root_ident = match input.ident.clone() {
+        StructName::Named(v) => v,
+        StructName::Unnamed(_, span) => {
+            return Err(syn::Error::new(
+                span,
+                "unnamed root struct is not supported; provide a name for the struct",
+            ));
+        }
+    };
+
+    lower(
+        root_ident,
+        input.visibility,
+        input.extra_macros.clone(),
+        DeriveBox::Struct(Box::new(input)),
+    )
+}
+
+fn lower_enum(input: DeriveEnum) -> syn::Result<Expansion> {
+    let input = input.hoist_attr_macros();
+    let root_ident = match input.ident.clone() {
+        StructName::Named(v) => v,
+        StructName::Unnamed(_, span) => {
+            return Err(syn::Error::new(
+                span,
+                "unnamed root enum is not supported; provide a name for the enum",
+            ));
+        }
+    };
+
+    lower(
+        root_ident,
+        input.visibility,
+        input.extra_macros.clone(),
+        DeriveBox::Enum(Box::new(input)),
+    )
+}
+
+fn lower(
+    root_ident: Ident,
+    visibility: DeriveVisibility,
+    extra_macros: ExtraMacros,
+    root: DeriveBox,
+) -> syn::Result<Expansion> {
+    let mod_ident = syn::Ident::new(&format!("__{}", root_ident), root_ident.span());
+    validate(&root)?;
+    let (structs, enums) = flatten(
+        &FlattenContext::new(root_ident.to_string(), extra_macros.anonymous_naming),
+        root,
+    )?;
+    check_unique_names(&structs, &enums)?;
+
+    Ok(Expansion {
+        is_public: visibility == DeriveVisibility::Public,
+        macro_visibility: extra_macros.macros_visibility,
+        mod_ident,
+        structs,
+        enums,
+    })
+}
+
+fn expand_struct(input: DeriveStruct) -> TokenStream {
+    match lower_struct(input) {
+        Ok(expansion) => expand(expansion),
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn expand(expansion: Expansion) -> TokenStream {
+    let Expansion {
+        is_public,
+        macro_visibility,
+        mod_ident,
+        structs,
+        enums,
+    } = expansion;
+
+    let structs_auto_macros = generate_structs_auto_macros(structs.clone(), macro_visibility);
+    let enums_auto_macros = generate_enums_auto_macros(enums.clone(), macro_visibility);
+    let structs_ty_macros = generate_structs_ty_macros(structs.clone(), macro_visibility);
+    let enums_ty_macros = generate_enums_ty_macros(enums.clone(), macro_visibility);
+
+    let structs = generate_structs_quote(structs);
+    let enums = generate_enums_quote(enums);
+
+    if is_public {
+        quote! {
+            #[macro_use]
+            #[allow(
+                non_camel_case_types,
+                non_snake_case,
+                non_upper_case_globals,
+                dead_code,
+                clippy::all
+            )]
+            pub mod #mod_ident {
+                use super::*;
+
+                #( #structs )*
+                #( #enums )*
+
+                #( #structs_auto_macros )*
+                #( #enums_auto_macros )*
+
+                #( #structs_ty_macros )*
+                #( #enums_ty_macros )*
+            }
+
+            pub use #mod_ident::*;
+        }
+    } else {
+        quote! {
+            #[macro_use]
+            #[allow(
+                non_camel_case_types,
+                non_snake_case,
+                non_upper_case_globals,
+                dead_code,
+                clippy::all
+            )]
+            pub(crate) mod #mod_ident {
+                use super::*;
+
+                #( #structs )*
+                #( #enums )*
+
+                #( #structs_auto_macros )*
+                #( #enums_auto_macros )*
+
+                #( #structs_ty_macros )*
+                #( #enums_ty_macros )*
+            }
+
+            pub(crate) use #mod_ident::*;
+        }
+    }
+}
+
+/// The expansion of `derive_enum!`.
+pub fn derive_enum(input: TokenStream) -> TokenStream {
+    let input = parse_input!(input as DeriveEnum);
+
+    match lower_enum(input) {
+        Ok(expansion) => expand(expansion),
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+/// The expansion of `derive_struct_file!`.
+pub fn derive_struct_file(input: TokenStream) -> TokenStream {
+    let input = parse_input!(input as DeriveFile);
+
+    expand_file(input, |tokens| {
+        syn::parse2::<DeriveStruct>(tokens).and_then(lower_struct)
+    })
+}
+
+/// The expansion of `derive_enum_file!`.
+pub fn derive_enum_file(input: TokenStream) -> TokenStream {
+    let input = parse_input!(input as DeriveFile);
+
+    expand_file(input, |tokens| {
+        syn::parse2::<DeriveEnum>(tokens).and_then(lower_enum)
+    })
+}
+
+fn expand_file(
+    input: DeriveFile,
+    lower: impl Fn(TokenStream) -> syn::Result<Expansion>,
+) -> TokenStream {
+    let (source, path) = match input.read() {
+        Ok(v) => v,
+        Err(err) => return err.to_compile_error(),
+    };
+    let expanded = match input.lower(&source, lower) {
+        Ok(expansion) => expand(expansion),
+        Err(err) => err.to_compile_error(),
+    };
+
+    quote! {
+        // Rebuild when the file changes.
+        const _: &str = include_str!(#path);
+
+        #expanded
+    }
+}
+
+/// The expansion of `auto!`.
+pub fn auto(input: TokenStream) -> TokenStream {
+    let input = parse_input!(input as AutoMacros);
+    let ident = input.ident.clone();
+    let body = input.body;
+
+    let macro_ident = Ident::new(&format!("__auto_{}", input.ident), input.ident.span());
+    match body {
+        AutoMacrosType::Struct {
+            items,
+            expand_exprs,
+        } => {
+            let list = items
+                .iter()
+                .map(|(key, value)| {
+                    quote! {
+                        #key: #macro_ident!(#key #value)
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            if let Some(expand_exprs) = expand_exprs {
+                quote! {
+                    #ident {
+                        #( #list ),*,
+                        ..#expand_exprs
+                    }
+                }
+            } else {
+                quote! {
+                    #ident {
+                        #( #list ),*
+                    }
+                }
+            }
+        }
+
+        AutoMacrosType::EnumEmpty { key } => quote! {
+            #ident::#key
+        },
+        AutoMacrosType::EnumStruct {
+            key,
+            items,
+            expand_exprs,
+        } => {
+            let list = items
+                .iter()
+                .map(|(item_key, value)| {
+                    quote! {
+                        #item_key: #macro_ident!(#key #item_key #value)
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            if let Some(expand_exprs) = expand_exprs {
+                quote! {
+                    #ident::#key {
+                        #( #list ),*,
+                        ..#expand_exprs
+                    }
+                }
+            } else {
+                quote! {
+                    #ident::#key {
+                        #( #list ),*
+                    }
+                }
+            }
+        }
+        AutoMacrosType::EnumTuple { key, items } => {
+            if let [first_item] = items.as_slice() {
+                quote! {
+                    #ident::#key(#macro_ident!(#key #first_item))
+                }
+            } else {
+                let list = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| {
+                        quote! {
+                            #macro_ident!(#key #index #item)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                quote! {
+                    #ident::#key(#( #list ),*)
+                }
+            }
+        }
+        AutoMacrosType::EnumSinglePath { key, next_key } => quote! {
+            #ident::#key(#macro_ident!(#key 0 #next_key))
+        },
+
+        AutoMacrosType::Value { items } => {
+            if let [first_item] = items.as_slice() {
+                quote! {
+                    #first_item
+                }
+            } else {
+                let list = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| {
+                        quote! {
+                            #macro_ident!(#index #item)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                quote! {
+                    (#( #list ),*)
+                }
+            }
+        }
+    }
+}
+
+/// The expansion of `ty!`.
+pub fn ty(input: TokenStream) -> TokenStream {
+    let input = parse_input!(input as TyMacros);
+    let ident = input.ident.clone();
+    let path = input.path;
+
+    if path.is_empty() {
+        return quote! {
+            #ident
+        };
+    }
+
+    let macro_ident = Ident::new(&format!("__ty_{}", input.ident), input.ident.span());
+    quote! {
+        #macro_ident!(#( #path ).*)
+    }
+}
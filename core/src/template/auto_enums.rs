@@ -13,7 +13,7 @@ pub(crate) fn generate_enums_auto_macros(
         .map(|(ident, v, _default_value, _extra_macros)| {
             let rules = v
                 .iter()
-                .map(|(name, ty, _)| match ty {
+                .map(|(name, ty, _, _)| match ty {
                     EnumValueFlatten::Empty => {
                         quote! {}
                     }
@@ -0,0 +1,156 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, Ident};
+
+use crate::tools::{EnumValueFlatten, StructMembersFlatten, TypeFlatten, TypeFlattenKind};
+
+/// Compare one field, recursing into inline types and treating any other type
+/// as a leaf.
+fn diff_field(
+    before: TokenStream,
+    after: TokenStream,
+    path: TokenStream,
+    ty: &TypeFlatten,
+) -> TokenStream {
+    match ty.kind {
+        TypeFlattenKind::InlineStruct | TypeFlattenKind::InlineEnum => quote! {
+            ::yuuka::Diff::diff_into(#before, #after, &#path, changes);
+        },
+        TypeFlattenKind::Static => quote! {
+            ::yuuka::diff::diff_leaf(#before, #after, &#path, changes);
+        },
+    }
+}
+
+fn diff_impl(ident: &Ident, body: TokenStream) -> TokenStream {
+    quote! {
+        impl ::yuuka::Diff for #ident {
+            fn diff_into(&self, other: &Self, path: &str, changes: &mut Vec<::yuuka::Change>) {
+                #body
+            }
+        }
+
+        impl #ident {
+            /// List the leaves that differ between `self` and `other`.
+            pub fn diff(&self, other: &Self) -> Vec<::yuuka::Change> {
+                let mut changes = vec![];
+                ::yuuka::Diff::diff_into(self, other, "", &mut changes);
+                changes
+            }
+        }
+    }
+}
+
+pub(crate) fn generate_struct_diff(ident: &Ident, members: &StructMembersFlatten) -> TokenStream {
+    let fields = members
+        .iter()
+        .map(|(key, ty, _, _)| {
+            let name = key.unraw().to_string();
+            diff_field(
+                quote! { &self.#key },
+                quote! { &other.#key },
+                quote! { ::yuuka::diff::field_path(path, #name) },
+                ty,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    diff_impl(
+        ident,
+        quote! {
+            #( #fields )*
+        },
+    )
+}
+
+pub(crate) fn generate_enum_diff(
+    ident: &Ident,
+    variants: &[(Ident, EnumValueFlatten, Option<u32>, Vec<TokenStream>)],
+) -> TokenStream {
+    let arms = variants
+        .iter()
+        .map(|(key, value, _, _)| {
+            let name = key.unraw().to_string();
+            let variant_path = quote! { ::yuuka::diff::field_path(path, #name) };
+
+            match value {
+                EnumValueFlatten::Empty => quote! {
+                    (Self::#key, Self::#key) => {}
+                },
+                EnumValueFlatten::Tuple(items) => {
+                    let before = (0..items.len())
+                        .map(|index| format_ident!("before_{}", index))
+                        .collect::<Vec<_>>();
+                    let after = (0..items.len())
+                        .map(|index| format_ident!("after_{}", index))
+                        .collect::<Vec<_>>();
+                    let fields = items
+                        .iter()
+                        .enumerate()
+                        .map(|(index, ty)| {
+                            let (before, after) = (&before[index], &after[index]);
+                            // A single item is named by the variant alone, like in `ty!`.
+                            let path = if items.len() == 1 {
+                                variant_path.clone()
+                            } else {
+                                let index = index.to_string();
+                                quote! { ::yuuka::diff::field_path(&#variant_path, #index) }
+                            };
+                            diff_field(quote! { #before }, quote! { #after }, path, ty)
+                        })
+                        .collect::<Vec<_>>();
+
+                    quote! {
+                        (Self::#key(#(#before),*), Self::#key(#(#after),*)) => {
+                            #( #fields )*
+                        }
+                    }
+                }
+                EnumValueFlatten::Struct(items) => {
+                    let keys = items.iter().map(|(key, _, _, _)| key).collect::<Vec<_>>();
+                    let before = (0..items.len())
+                        .map(|index| format_ident!("before_{}", index))
+                        .collect::<Vec<_>>();
+                    let after = (0..items.len())
+                        .map(|index| format_ident!("after_{}", index))
+                        .collect::<Vec<_>>();
+                    let fields = items
+                        .iter()
+                        .enumerate()
+                        .map(|(index, (key, ty, _, _))| {
+                            let (before, after) = (&before[index], &after[index]);
+                            let name = key.unraw().to_string();
+                            diff_field(
+                                quote! { #before },
+                                quote! { #after },
+                                quote! { ::yuuka::diff::field_path(&#variant_path, #name) },
+                                ty,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+
+                    quote! {
+                        (
+                            Self::#key { #(#keys: #before),* },
+                            Self::#key { #(#keys: #after),* },
+                        ) => {
+                            #( #fields )*
+                        }
+                    }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    diff_impl(
+        ident,
+        quote! {
+            #[allow(unreachable_patterns)]
+            match (self, other) {
+                #( #arms )*
+                // Different variants are reported as a whole.
+                _ => ::yuuka::diff::changed(self, other, path, changes),
+            }
+        },
+    )
+}
@@ -1,6 +1,10 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
+use super::{
+    diff::generate_enum_diff, example::generate_enum_example, schema::generate_enum_schema,
+    serde_attrs::generate_field_serde_attrs,
+};
 use crate::tools::{DefaultValue, EnumValueFlatten, EnumsFlatten};
 
 pub(crate) fn generate_enums_quote(enums: EnumsFlatten) -> Vec<TokenStream> {
@@ -9,8 +13,8 @@ pub(crate) fn generate_enums_quote(enums: EnumsFlatten) -> Vec<TokenStream> {
         .map(|(k, v, default_value, extra_macros)| {
             let keys = v
                 .iter()
-                .map(|(key, ty, extra_macros)| {
-                    let extra_macros = extra_macros
+                .map(|(key, ty, _, variant_extra_macros)| {
+                    let variant_extra_macros = variant_extra_macros
                         .iter()
                         .map(|content| {
                             quote! {
@@ -21,18 +25,18 @@ pub(crate) fn generate_enums_quote(enums: EnumsFlatten) -> Vec<TokenStream> {
 
                     match ty {
                         EnumValueFlatten::Empty => quote! {
-                            #(#extra_macros)*
+                            #(#variant_extra_macros)*
                             #key,
                         },
                         EnumValueFlatten::Tuple(v) => quote! {
-                            #(#extra_macros)*
+                            #(#variant_extra_macros)*
                             #key(#(#v),*),
                         },
                         EnumValueFlatten::Struct(v) => {
                             let keys = v
                                 .iter()
-                                .map(|(key, ty, _default_value, extra_macros)| {
-                                    let extra_macros = extra_macros
+                                .map(|(field_key, ty, _default_value, field_extra_macros)| {
+                                    let field_extra_macros = field_extra_macros
                                         .iter()
                                         .map(|content| {
                                             quote! {
@@ -41,15 +45,26 @@ pub(crate) fn generate_enums_quote(enums: EnumsFlatten) -> Vec<TokenStream> {
                                         })
                                         .collect::<Vec<_>>();
 
+                                    // Default values of struct variant fields are not
+                                    // used, so only the wrapper-based skips apply.
+                                    let serde_attrs = generate_field_serde_attrs(
+                                        &extra_macros.features,
+                                        k,
+                                        field_key,
+                                        ty,
+                                        &DefaultValue::None,
+                                    );
+
                                     quote! {
-                                        #(#extra_macros)*
-                                        #key: #ty,
+                                        #(#field_extra_macros)*
+                                        #serde_attrs
+                                        #field_key: #ty,
                                     }
                                 })
                                 .collect::<Vec<_>>();
 
                             quote! {
-                                #(#extra_macros)*
+                                #(#variant_extra_macros)*
                                 #key {
                                     #( #keys )*
                                 },
@@ -70,6 +85,22 @@ pub(crate) fn generate_enums_quote(enums: EnumsFlatten) -> Vec<TokenStream> {
                 quote! {}
             };
 
+            let diff = if extra_macros.features.diff {
+                generate_enum_diff(k, v)
+            } else {
+                quote! {}
+            };
+            let schema = if extra_macros.features.schema {
+                generate_enum_schema(k, v, default_value, extra_macros)
+            } else {
+                quote! {}
+            };
+            let example = if extra_macros.features.example {
+                generate_enum_example(k, v, default_value, extra_macros)
+            } else {
+                quote! {}
+            };
+
             let derive_macros = extra_macros.derive_macros.clone();
             let attr_macros = extra_macros.attr_macros.clone();
 
@@ -105,6 +136,9 @@ pub(crate) fn generate_enums_quote(enums: EnumsFlatten) -> Vec<TokenStream> {
                 }
 
                 #default_value_token
+                #diff
+                #schema
+                #example
             }
         })
         .collect::<Vec<_>>()
@@ -0,0 +1,135 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+use crate::tools::{
+    DefaultValue, EnumValueFlatten, ExtraMacrosFlatten, ExtraTypeWrapper, StructMembersFlatten,
+    TypeFlatten, TypeFlattenKind,
+};
+
+/// The example value of one field: its DSL default if it has one, otherwise
+/// the example of its inline type, or the `Default` of a type written in the
+/// DSL, with `?` fields filled and `[...]` fields given one item.
+fn example_value(ty: &TypeFlatten, default_value: &DefaultValue) -> TokenStream {
+    match default_value {
+        DefaultValue::Single(v) => return quote! { #v },
+        DefaultValue::Array(v) => return quote! { vec![#(#v),*] },
+        DefaultValue::None => {}
+    }
+
+    let inner = match ty.kind {
+        TypeFlattenKind::Static => quote! { Default::default() },
+        TypeFlattenKind::InlineStruct | TypeFlattenKind::InlineEnum => {
+            let inner = &ty.inner;
+            quote! { #inner::example() }
+        }
+    };
+    match ty.wrapper {
+        ExtraTypeWrapper::Default => inner,
+        ExtraTypeWrapper::Vec => quote! { vec![#inner] },
+        ExtraTypeWrapper::Option => quote! { Some(#inner) },
+        ExtraTypeWrapper::OptionVec => quote! { Some(vec![#inner]) },
+    }
+}
+
+fn example_impl(
+    ident: &Ident,
+    extra_macros: &ExtraMacrosFlatten,
+    body: TokenStream,
+) -> TokenStream {
+    // Converting to JSON needs `Serialize`, which is only known to be there
+    // when it is derived.
+    let is_serialize = extra_macros.derive_macros.iter().any(|derive| {
+        derive
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Serialize")
+    });
+    let example_json = if is_serialize {
+        quote! {
+            /// [`Self::example`] as JSON.
+            pub fn example_json() -> ::yuuka::example::Value {
+                ::yuuka::example::to_json(&Self::example())
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        impl #ident {
+            /// A value with every field filled, for example documents.
+            pub fn example() -> Self {
+                #body
+            }
+
+            #example_json
+        }
+    }
+}
+
+fn struct_fields(members: &StructMembersFlatten) -> Vec<TokenStream> {
+    members
+        .iter()
+        .map(|(key, ty, default_value, _)| {
+            let value = example_value(ty, default_value);
+            quote! { #key: #value, }
+        })
+        .collect()
+}
+
+pub(crate) fn generate_struct_example(
+    ident: &Ident,
+    members: &StructMembersFlatten,
+    extra_macros: &ExtraMacrosFlatten,
+) -> TokenStream {
+    let fields = struct_fields(members);
+
+    example_impl(
+        ident,
+        extra_macros,
+        quote! {
+            Self {
+                #( #fields )*
+            }
+        },
+    )
+}
+
+pub(crate) fn generate_enum_example(
+    ident: &Ident,
+    variants: &[(Ident, EnumValueFlatten, Option<u32>, Vec<TokenStream>)],
+    default_value: &DefaultValue,
+    extra_macros: &ExtraMacrosFlatten,
+) -> TokenStream {
+    let body = match (default_value, variants.first()) {
+        (DefaultValue::Single(v), _) => quote! { #v },
+        (_, Some((key, EnumValueFlatten::Empty, _, _))) => quote! { Self::#key },
+        (_, Some((key, EnumValueFlatten::Tuple(items), _, _))) => {
+            let items = items
+                .iter()
+                .map(|ty| example_value(ty, &DefaultValue::None))
+                .collect::<Vec<_>>();
+            quote! { Self::#key(#(#items),*) }
+        }
+        (_, Some((key, EnumValueFlatten::Struct(members), _, _))) => {
+            let fields = struct_fields(members);
+            quote! {
+                Self::#key {
+                    #( #fields )*
+                }
+            }
+        }
+        // An enum without variants has no value to return.
+        (_, None) => {
+            return syn::Error::new(
+                ident.span(),
+                format!("`example` needs at least one variant in `{}`", ident),
+            )
+            .to_compile_error();
+        }
+    };
+
+    example_impl(ident, extra_macros, body)
+}
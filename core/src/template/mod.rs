@@ -1,9 +1,18 @@
 pub(crate) mod auto_enums;
 pub(crate) mod auto_structs;
+pub(crate) mod diff;
 pub(crate) mod enums;
+pub(crate) mod example;
+pub(crate) mod partial;
+pub(crate) mod schema;
+pub(crate) mod serde_attrs;
 pub(crate) mod structs;
+pub(crate) mod ty_enums;
+pub(crate) mod ty_structs;
 
 pub(crate) use auto_enums::generate_enums_auto_macros;
 pub(crate) use auto_structs::generate_structs_auto_macros;
 pub(crate) use enums::generate_enums_quote;
 pub(crate) use structs::generate_structs_quote;
+pub(crate) use ty_enums::generate_enums_ty_macros;
+pub(crate) use ty_structs::generate_structs_ty_macros;
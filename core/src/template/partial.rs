@@ -0,0 +1,215 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{punctuated::Punctuated, Ident, Meta, Token, TypePath};
+
+use crate::tools::{ExtraMacrosFlatten, ExtraTypeWrapper, StructMembersFlatten, TypeFlattenKind};
+
+pub(crate) fn partial_ident(ident: &Ident) -> Ident {
+    format_ident!("{}Partial", ident)
+}
+
+fn partial_inner(inner: &TypePath) -> TypePath {
+    let mut inner = inner.clone();
+    if let Some(segment) = inner.path.segments.last_mut() {
+        segment.ident = partial_ident(&segment.ident);
+    }
+    inner
+}
+
+/// The serde options of a field that still mean the same on its partial twin,
+/// whose type is wrapped in `Option`. Options like `default = "..."` or
+/// `skip_serializing_if` name functions of the original type.
+const PARTIAL_SERDE_OPTIONS: &[&str] = &[
+    "rename",
+    "alias",
+    "skip",
+    "skip_serializing",
+    "skip_deserializing",
+];
+
+/// A field attribute as it goes on the partial twin, with the serde options
+/// that depend on the type of the field left out, or `None` if nothing is
+/// left of it.
+fn partial_field_attr(attr: &TokenStream) -> Option<TokenStream> {
+    let Ok(Meta::List(list)) = syn::parse2::<Meta>(attr.clone()) else {
+        return Some(attr.clone());
+    };
+    if !list.path.is_ident("serde") {
+        return Some(attr.clone());
+    }
+    let Ok(items) = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated) else {
+        return Some(attr.clone());
+    };
+
+    let items = items
+        .into_iter()
+        .filter(|item| {
+            PARTIAL_SERDE_OPTIONS
+                .iter()
+                .any(|option| item.path().is_ident(option))
+        })
+        .collect::<Vec<_>>();
+    (!items.is_empty()).then(|| quote! { serde(#(#items),*) })
+}
+
+/// How a field of a struct is represented in its partial twin.
+enum PartialField {
+    /// `Option<T>`, replacing the whole value when set.
+    Replace,
+    /// `Option<InnerPartial>`, merged into a plain inline struct.
+    Merge,
+    /// `Option<InnerPartial>`, merged into a `?` inline struct.
+    MergeOption,
+}
+
+/// The `{Ident}Partial` twin of a struct, with every field optional, together
+/// with `merge` on the struct and `overlay` on the twin.
+pub(crate) fn generate_struct_partial(
+    ident: &Ident,
+    members: &StructMembersFlatten,
+    extra_macros: &ExtraMacrosFlatten,
+) -> TokenStream {
+    let partial = partial_ident(ident);
+
+    let fields = members
+        .iter()
+        .map(|(key, ty, _, field_extra_macros)| {
+            let kind = match (ty.kind, ty.wrapper) {
+                (TypeFlattenKind::InlineStruct, ExtraTypeWrapper::Default) => PartialField::Merge,
+                (TypeFlattenKind::InlineStruct, ExtraTypeWrapper::Option) => {
+                    PartialField::MergeOption
+                }
+                _ => PartialField::Replace,
+            };
+            (key, ty, kind, field_extra_macros)
+        })
+        .collect::<Vec<_>>();
+
+    let keys = fields
+        .iter()
+        .map(|(key, ty, kind, field_extra_macros)| {
+            let field_extra_macros = field_extra_macros
+                .iter()
+                .filter_map(partial_field_attr)
+                .map(|content| {
+                    quote! {
+                        #[#content]
+                    }
+                })
+                .collect::<Vec<_>>();
+            let skip_none = if extra_macros.features.skip_none {
+                quote! {
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                }
+            } else {
+                quote! {}
+            };
+            let ty = match kind {
+                PartialField::Replace => match ty.wrapper {
+                    // `?` fields are already optional, `None` means unset.
+                    ExtraTypeWrapper::Option | ExtraTypeWrapper::OptionVec => quote! { #ty },
+                    _ => quote! { Option<#ty> },
+                },
+                PartialField::Merge | PartialField::MergeOption => {
+                    let inner = partial_inner(&ty.inner);
+                    quote! { Option<#inner> }
+                }
+            };
+
+            quote! {
+                #(#field_extra_macros)*
+                #skip_none
+                pub #key: #ty,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let merges = fields
+        .iter()
+        .map(|(key, ty, kind, _)| match kind {
+            PartialField::Replace => match ty.wrapper {
+                ExtraTypeWrapper::Option | ExtraTypeWrapper::OptionVec => quote! {
+                    if partial.#key.is_some() {
+                        self.#key = partial.#key;
+                    }
+                },
+                _ => quote! {
+                    if let Some(value) = partial.#key {
+                        self.#key = value;
+                    }
+                },
+            },
+            PartialField::Merge => quote! {
+                if let Some(value) = partial.#key {
+                    self.#key.merge(value);
+                }
+            },
+            PartialField::MergeOption => quote! {
+                if let Some(value) = partial.#key {
+                    self.#key.get_or_insert_with(Default::default).merge(value);
+                }
+            },
+        })
+        .collect::<Vec<_>>();
+
+    let overlays = fields
+        .iter()
+        .map(|(key, ty, kind, _)| match kind {
+            PartialField::Replace => quote! {
+                #key: top.#key.or(base.#key),
+            },
+            PartialField::Merge | PartialField::MergeOption => {
+                let inner = partial_inner(&ty.inner);
+                quote! {
+                    #key: match (base.#key, top.#key) {
+                        (Some(base), Some(top)) => Some(#inner::overlay(base, top)),
+                        (base, top) => top.or(base),
+                    },
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let derive_macros = &extra_macros.derive_macros;
+    let derive_macros = if derive_macros.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            #[derive(#(#derive_macros),*)]
+        }
+    };
+    let attr_macros = extra_macros
+        .attr_macros
+        .iter()
+        .map(|content| {
+            quote! {
+               #[#content]
+            }
+        })
+        .collect::<Vec<_>>();
+
+    quote! {
+        #[derive(Debug, Clone, Default)]
+        #derive_macros
+        #(#attr_macros)*
+        pub struct #partial {
+            #( #keys )*
+        }
+
+        impl #partial {
+            /// Layer `top` over `base`, keeping the fields of `top` that are set.
+            pub fn overlay(base: Self, top: Self) -> Self {
+                Self {
+                    #( #overlays )*
+                }
+            }
+        }
+
+        impl #ident {
+            /// Overwrite the fields that are set in `partial`.
+            pub fn merge(&mut self, partial: #partial) {
+                #( #merges )*
+            }
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{ext::IdentExt, Expr, ExprLit, Ident, Lit, Meta, MetaNameValue};
+
+use crate::{
+    tools::{
+        DefaultValue, DeriveFeatures, EnumValueFlatten, ExtraMacrosFlatten, ExtraTypeWrapper,
+        StructMembersFlatten, TypeFlatten, TypeFlattenKind,
+    },
+    utils::{literal_json, pretty_inline, RenameRule, SerdeAttrs},
+};
+
+/// The text of the `///` comments among some attributes, one line per
+/// comment, or `None` without any.
+fn doc_text(attrs: &[TokenStream]) -> TokenStream {
+    let lines = attrs
+        .iter()
+        .filter_map(|attr| match syn::parse2::<Meta>(attr.clone()).ok()? {
+            Meta::NameValue(MetaNameValue {
+                path,
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(text),
+                        ..
+                    }),
+                ..
+            }) if path.is_ident("doc") => Some(text.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_string).unwrap_or(line))
+        .collect::<Vec<_>>();
+    option_text((!lines.is_empty()).then(|| lines.join("\n")))
+}
+
+fn texts(list: impl IntoIterator<Item = impl ToTokens>) -> TokenStream {
+    let list = list.into_iter().map(pretty_inline).collect::<Vec<_>>();
+    quote! { &[#(#list),*] }
+}
+
+/// What a field needs to know about its surroundings to describe how serde
+/// treats it.
+struct FieldContext<'a> {
+    rename_all: Option<RenameRule>,
+    /// `#[serde(default)]` on the type, or `serde_default` on the struct.
+    container_default: bool,
+    /// Whether the DSL defaults of the fields are used. They are not for the
+    /// fields of struct variants.
+    dsl_defaults: bool,
+    features: &'a DeriveFeatures,
+}
+
+fn field_schema(
+    index: usize,
+    name: String,
+    ty: &TypeFlatten,
+    default_value: &DefaultValue,
+    attrs: &[TokenStream],
+    context: &FieldContext,
+) -> TokenStream {
+    let default_value = if context.dsl_defaults {
+        default_value
+    } else {
+        &DefaultValue::None
+    };
+    let serde = SerdeAttrs::parse(attrs);
+    let serde_name = serde.rename.clone().unwrap_or_else(|| {
+        context
+            .rename_all
+            .map(|rule| rule.apply_to_field(&name))
+            .unwrap_or_else(|| name.clone())
+    });
+    // Mirrors the `#[serde(default ...)]` that `serde_default` adds.
+    let serde_default = serde.default
+        || context.container_default
+        || (context.features.serde_default
+            && (default_value != &DefaultValue::None || ty.inline_default()));
+    let serde_skip = serde.skip;
+
+    let full_ty = pretty_inline(&ty.ty);
+    let inner = match ty.kind {
+        TypeFlattenKind::Static => {
+            let inner = pretty_inline(&ty.inner);
+            quote! { ::yuuka::schema::TypeRef::Static(#inner) }
+        }
+        TypeFlattenKind::InlineStruct | TypeFlattenKind::InlineEnum => {
+            let inner = &ty.inner;
+            quote! { ::yuuka::schema::TypeRef::Generated(<#inner as ::yuuka::Schema>::schema) }
+        }
+    };
+    let wrapper = match ty.wrapper {
+        ExtraTypeWrapper::Default => quote! { ::yuuka::schema::Wrapper::Plain },
+        ExtraTypeWrapper::Vec => quote! { ::yuuka::schema::Wrapper::Vec },
+        ExtraTypeWrapper::Option => quote! { ::yuuka::schema::Wrapper::Option },
+        ExtraTypeWrapper::OptionVec => quote! { ::yuuka::schema::Wrapper::OptionVec },
+    };
+    let (default_text, default_json) = match default_value {
+        DefaultValue::None => (None, None),
+        DefaultValue::Single(v) => (Some(pretty_inline(v)), literal_json(v)),
+        DefaultValue::Array(v) => (
+            Some(pretty_inline(quote! { vec![#(#v),*] })),
+            v.iter()
+                .map(literal_json)
+                .collect::<Option<Vec<_>>>()
+                .map(serde_json::Value::Array),
+        ),
+    };
+    let default_text = option_text(default_text);
+    let default_json = option_text(default_json.map(|v| v.to_string()));
+    let proto_number = ty.proto_number.unwrap_or(index as u32 + 1);
+    let docs = doc_text(attrs);
+    let attrs = texts(attrs);
+
+    quote! {
+        ::yuuka::schema::FieldSchema {
+            name: #name,
+            ty: #full_ty,
+            inner: #inner,
+            wrapper: #wrapper,
+            default: #default_text,
+            attrs: #attrs,
+            default_json: #default_json,
+            serde_name: #serde_name,
+            serde_default: #serde_default,
+            serde_skip: #serde_skip,
+            proto_number: #proto_number,
+            docs: #docs,
+        }
+    }
+}
+
+fn option_text(text: Option<String>) -> TokenStream {
+    match text {
+        Some(v) => quote! { Some(#v) },
+        None => quote! { None },
+    }
+}
+
+fn fields_schema(members: &StructMembersFlatten, context: &FieldContext) -> TokenStream {
+    let fields = members
+        .iter()
+        .enumerate()
+        .map(|(index, (key, ty, default_value, attrs))| {
+            field_schema(
+                index,
+                key.unraw().to_string(),
+                ty,
+                default_value,
+                attrs,
+                context,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    quote! { &[#(#fields),*] }
+}
+
+fn schema_impl(ident: &Ident, schema: TokenStream) -> TokenStream {
+    quote! {
+        impl ::yuuka::Schema for #ident {
+            fn schema() -> &'static ::yuuka::schema::TypeSchema {
+                static SCHEMA: ::yuuka::schema::TypeSchema = #schema;
+                &SCHEMA
+            }
+        }
+    }
+}
+
+pub(crate) fn generate_struct_schema(
+    ident: &Ident,
+    members: &StructMembersFlatten,
+    extra_macros: &ExtraMacrosFlatten,
+) -> TokenStream {
+    let name = ident.to_string();
+    let anonymous = extra_macros.anonymous;
+    let serde = SerdeAttrs::parse(&extra_macros.attr_macros);
+    let fields = fields_schema(
+        members,
+        &FieldContext {
+            rename_all: serde.rename_all,
+            container_default: serde.default,
+            dsl_defaults: true,
+            features: &extra_macros.features,
+        },
+    );
+    let derives = texts(&extra_macros.derive_macros);
+    let docs = doc_text(&extra_macros.attr_macros);
+    let attrs = texts(&extra_macros.attr_macros);
+    let deny_unknown_fields = serde.deny_unknown_fields;
+
+    schema_impl(
+        ident,
+        quote! {
+            ::yuuka::schema::TypeSchema::Struct(::yuuka::schema::StructSchema {
+                name: #name,
+                anonymous: #anonymous,
+                fields: #fields,
+                derives: #derives,
+                attrs: #attrs,
+                deny_unknown_fields: #deny_unknown_fields,
+                docs: #docs,
+            })
+        },
+    )
+}
+
+pub(crate) fn generate_enum_schema(
+    ident: &Ident,
+    variants: &[(Ident, EnumValueFlatten, Option<u32>, Vec<TokenStream>)],
+    default_value: &DefaultValue,
+    extra_macros: &ExtraMacrosFlatten,
+) -> TokenStream {
+    let name = ident.to_string();
+    let anonymous = extra_macros.anonymous;
+    let serde = SerdeAttrs::parse(&extra_macros.attr_macros);
+    // Unit-only enums become protobuf enums, whose values start from 0, and
+    // the others a `oneof`, whose field numbers start from 1.
+    let first_proto_number = if variants
+        .iter()
+        .all(|(_, value, _, _)| matches!(value, EnumValueFlatten::Empty))
+    {
+        0
+    } else {
+        1
+    };
+    let variants = variants
+        .iter()
+        .enumerate()
+        .map(|(index, (key, value, proto_number, attrs))| {
+            let name = key.unraw().to_string();
+            let variant_serde = SerdeAttrs::parse(attrs);
+            let serde_name = variant_serde.rename.clone().unwrap_or_else(|| {
+                serde
+                    .rename_all
+                    .map(|rule| rule.apply_to_variant(&name))
+                    .unwrap_or_else(|| name.clone())
+            });
+            let serde_skip = variant_serde.skip;
+            let proto_number = proto_number.unwrap_or(index as u32 + first_proto_number);
+            let context = FieldContext {
+                rename_all: variant_serde.rename_all.or(serde.rename_all_fields),
+                container_default: false,
+                dsl_defaults: false,
+                features: &extra_macros.features,
+            };
+            let shape = match value {
+                EnumValueFlatten::Empty => quote! { ::yuuka::schema::VariantShape::Unit },
+                EnumValueFlatten::Tuple(items) => {
+                    let items = items
+                        .iter()
+                        .enumerate()
+                        .map(|(index, ty)| {
+                            field_schema(
+                                index,
+                                index.to_string(),
+                                ty,
+                                &DefaultValue::None,
+                                &[],
+                                &context,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    quote! { ::yuuka::schema::VariantShape::Tuple(&[#(#items),*]) }
+                }
+                EnumValueFlatten::Struct(members) => {
+                    let fields = fields_schema(members, &context);
+                    quote! { ::yuuka::schema::VariantShape::Struct(#fields) }
+                }
+            };
+            let docs = doc_text(attrs);
+            let attrs = texts(attrs);
+
+            quote! {
+                ::yuuka::schema::VariantSchema {
+                    name: #name,
+                    shape: #shape,
+                    attrs: #attrs,
+                    serde_name: #serde_name,
+                    serde_skip: #serde_skip,
+                    proto_number: #proto_number,
+                    docs: #docs,
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    let default_value = match default_value {
+        DefaultValue::Single(v) => {
+            let v = pretty_inline(v);
+            quote! { Some(#v) }
+        }
+        _ => quote! { None },
+    };
+    let derives = texts(&extra_macros.derive_macros);
+    let docs = doc_text(&extra_macros.attr_macros);
+    let attrs = texts(&extra_macros.attr_macros);
+    let tagging = match (serde.untagged, serde.tag, serde.content) {
+        (true, _, _) => quote! { ::yuuka::schema::Tagging::Untagged },
+        (false, Some(tag), Some(content)) => {
+            quote! { ::yuuka::schema::Tagging::Adjacent { tag: #tag, content: #content } }
+        }
+        (false, Some(tag), None) => quote! { ::yuuka::schema::Tagging::Internal { tag: #tag } },
+        (false, None, _) => quote! { ::yuuka::schema::Tagging::External },
+    };
+
+    schema_impl(
+        ident,
+        quote! {
+            ::yuuka::schema::TypeSchema::Enum(::yuuka::schema::EnumSchema {
+                name: #name,
+                anonymous: #anonymous,
+                variants: &[#(#variants),*],
+                default: #default_value,
+                derives: #derives,
+                attrs: #attrs,
+                tagging: #tagging,
+                docs: #docs,
+            })
+        },
+    )
+}
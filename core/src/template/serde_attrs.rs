@@ -0,0 +1,122 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, Ident};
+
+use crate::tools::{DefaultValue, DeriveFeatures, ExtraTypeWrapper, TypeFlatten};
+
+fn serde_default_fn_ident(ident: &Ident, key: &Ident) -> Ident {
+    format_ident!("__yuuka_default_{}_{}", ident, key.unraw())
+}
+
+fn is_default_fn_ident(ident: &Ident, key: &Ident) -> Ident {
+    format_ident!("__yuuka_is_default_{}_{}", ident, key.unraw())
+}
+
+fn default_value_quote(default_value: &DefaultValue) -> Option<TokenStream> {
+    match default_value {
+        DefaultValue::None => None,
+        DefaultValue::Single(v) => Some(quote! { #v }),
+        DefaultValue::Array(v) => Some(quote! { vec![#(#v),*] }),
+    }
+}
+
+/// The serde attributes the enabled features add to the field `key` of the
+/// type `ident`.
+pub(crate) fn generate_field_serde_attrs(
+    features: &DeriveFeatures,
+    ident: &Ident,
+    key: &Ident,
+    ty: &TypeFlatten,
+    default_value: &DefaultValue,
+) -> TokenStream {
+    let has_default_value = default_value != &DefaultValue::None;
+
+    let serde_default = if !features.serde_default {
+        quote! {}
+    } else if has_default_value {
+        let path = serde_default_fn_ident(ident, key).to_string();
+        quote! {
+            #[serde(default = #path)]
+        }
+    } else if ty.inline_default() {
+        // Inline types carry the defaults of their own fields.
+        quote! {
+            #[serde(default)]
+        }
+    } else {
+        quote! {}
+    };
+
+    // serde accepts a single `skip_serializing_if`, so a DSL default wins over
+    // the wrapper of the field.
+    let skip_if = if features.skip_default && has_default_value {
+        Some(is_default_fn_ident(ident, key).to_string())
+    } else if features.skip_none
+        && matches!(
+            ty.wrapper,
+            ExtraTypeWrapper::Option | ExtraTypeWrapper::OptionVec
+        )
+    {
+        Some("Option::is_none".to_string())
+    } else if features.skip_empty && ty.wrapper == ExtraTypeWrapper::Vec {
+        Some("Vec::is_empty".to_string())
+    } else {
+        None
+    };
+    let skip_if = skip_if
+        .map(|path| {
+            quote! {
+                #[serde(skip_serializing_if = #path)]
+            }
+        })
+        .unwrap_or_default();
+
+    quote! {
+        #serde_default
+        #skip_if
+    }
+}
+
+/// The helper functions referenced by [`generate_field_serde_attrs`].
+pub(crate) fn generate_field_serde_fns(
+    features: &DeriveFeatures,
+    ident: &Ident,
+    key: &Ident,
+    ty: &TypeFlatten,
+    default_value: &DefaultValue,
+) -> TokenStream {
+    let Some(value) = default_value_quote(default_value) else {
+        return quote! {};
+    };
+
+    let serde_default = if features.serde_default {
+        let fn_ident = serde_default_fn_ident(ident, key);
+        quote! {
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            fn #fn_ident() -> #ty {
+                #value
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let is_default = if features.skip_default {
+        let fn_ident = is_default_fn_ident(ident, key);
+        quote! {
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            fn #fn_ident(value: &#ty) -> bool {
+                let default: #ty = #value;
+                *value == default
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        #serde_default
+        #is_default
+    }
+}
@@ -1,6 +1,13 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
+use super::{
+    diff::generate_struct_diff,
+    example::generate_struct_example,
+    partial::generate_struct_partial,
+    schema::generate_struct_schema,
+    serde_attrs::{generate_field_serde_attrs, generate_field_serde_fns},
+};
 use crate::tools::{DefaultValue, StructsFlatten};
 
 pub(crate) fn generate_structs_quote(structs: StructsFlatten) -> Vec<TokenStream> {
@@ -9,8 +16,8 @@ pub(crate) fn generate_structs_quote(structs: StructsFlatten) -> Vec<TokenStream
         .map(|(ident, v, extra_macros)| {
             let keys = v
                 .iter()
-                .map(|(key, ty, _default_value, extra_macros)| {
-                    let extra_macros = extra_macros
+                .map(|(key, ty, default_value, field_extra_macros)| {
+                    let field_extra_macros = field_extra_macros
                         .iter()
                         .map(|content| {
                             quote! {
@@ -18,14 +25,29 @@ pub(crate) fn generate_structs_quote(structs: StructsFlatten) -> Vec<TokenStream
                             }
                         })
                         .collect::<Vec<_>>();
+                    let serde_attrs = generate_field_serde_attrs(
+                        &extra_macros.features,
+                        ident,
+                        key,
+                        ty,
+                        default_value,
+                    );
 
                     quote! {
-                        #(#extra_macros)*
+                        #(#field_extra_macros)*
+                        #serde_attrs
                         pub #key: #ty,
                     }
                 })
                 .collect::<Vec<_>>();
 
+            let serde_fns = v
+                .iter()
+                .map(|(key, ty, default_value, _)| {
+                    generate_field_serde_fns(&extra_macros.features, ident, key, ty, default_value)
+                })
+                .collect::<Vec<_>>();
+
             let derive_macros = extra_macros.derive_macros.clone();
             let attr_macros = extra_macros.attr_macros.clone();
 
@@ -52,6 +74,27 @@ pub(crate) fn generate_structs_quote(structs: StructsFlatten) -> Vec<TokenStream
                 }
             };
 
+            let partial = if extra_macros.features.partial {
+                generate_struct_partial(ident, v, extra_macros)
+            } else {
+                quote! {}
+            };
+            let diff = if extra_macros.features.diff {
+                generate_struct_diff(ident, v)
+            } else {
+                quote! {}
+            };
+            let schema = if extra_macros.features.schema {
+                generate_struct_schema(ident, v, extra_macros)
+            } else {
+                quote! {}
+            };
+            let example = if extra_macros.features.example {
+                generate_struct_example(ident, v, extra_macros)
+            } else {
+                quote! {}
+            };
+
             if v.iter()
                 .all(|(_, _, default_value, _)| default_value == &DefaultValue::None)
             {
@@ -62,6 +105,11 @@ pub(crate) fn generate_structs_quote(structs: StructsFlatten) -> Vec<TokenStream
                     pub struct #ident {
                         #( #keys )*
                     }
+
+                    #partial
+                    #diff
+                    #schema
+                    #example
                 }
             } else {
                 let default_values = v
@@ -94,6 +142,13 @@ pub(crate) fn generate_structs_quote(structs: StructsFlatten) -> Vec<TokenStream
                             }
                         }
                     }
+
+                    #( #serde_fns )*
+
+                    #partial
+                    #diff
+                    #schema
+                    #example
                 }
             }
         })
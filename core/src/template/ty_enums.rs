@@ -0,0 +1,59 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use super::ty_structs::{generate_ty_macro, generate_ty_rules};
+use crate::tools::{DeriveAutoMacrosVisibility, EnumValueFlatten, EnumsFlatten};
+
+pub(crate) fn generate_enums_ty_macros(
+    enums: EnumsFlatten,
+    macros_visibility: DeriveAutoMacrosVisibility,
+) -> Vec<TokenStream> {
+    enums
+        .iter()
+        .map(|(ident, v, _default_value, _extra_macros)| {
+            let rules = v
+                .iter()
+                .map(|(name, ty, _, _)| match ty {
+                    EnumValueFlatten::Empty => {
+                        quote! {}
+                    }
+                    EnumValueFlatten::Struct(items) => {
+                        let list = items
+                            .iter()
+                            .map(|(key, ty, _default_value, _extra_macros)| {
+                                generate_ty_rules(quote! { #name . #key }, ty)
+                            })
+                            .collect::<Vec<_>>();
+                        quote! {
+                            #(#list)*
+                        }
+                    }
+                    EnumValueFlatten::Tuple(items) => {
+                        let list = items
+                            .iter()
+                            .enumerate()
+                            .map(|(i, ty)| {
+                                let i = syn::Index::from(i);
+                                generate_ty_rules(quote! { #name . #i }, ty)
+                            })
+                            .collect::<Vec<_>>();
+
+                        // Single-element tuple variants can skip the index,
+                        // just like `auto!(Enum::Variant::Next)`.
+                        let shortcut = if let [ty] = items.as_slice() {
+                            generate_ty_rules(quote! { #name }, ty)
+                        } else {
+                            quote! {}
+                        };
+
+                        quote! {
+                            #(#list)*
+                            #shortcut
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
+            generate_ty_macro(ident, quote! { #(#rules)* }, macros_visibility)
+        })
+        .collect::<Vec<_>>()
+}
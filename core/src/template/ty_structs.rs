@@ -0,0 +1,84 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+use crate::tools::{DeriveAutoMacrosVisibility, StructsFlatten, TypeFlatten, TypeFlattenKind};
+
+pub(crate) fn generate_structs_ty_macros(
+    structs: StructsFlatten,
+    macros_visibility: DeriveAutoMacrosVisibility,
+) -> Vec<TokenStream> {
+    structs
+        .iter()
+        .map(|(ident, v, _extra_macros)| {
+            let rules = v
+                .iter()
+                .map(|(name, ty, _, _)| generate_ty_rules(quote! { #name }, ty))
+                .collect::<Vec<_>>();
+            generate_ty_macro(ident, quote! { #(#rules)* }, macros_visibility)
+        })
+        .collect::<Vec<_>>()
+}
+
+/// Wrap the rules of a type into its `__ty_*` macro, which reports any other
+/// path as a compile error.
+pub(crate) fn generate_ty_macro(
+    ident: &Ident,
+    rules: TokenStream,
+    macros_visibility: DeriveAutoMacrosVisibility,
+) -> TokenStream {
+    let fallback = quote! {
+        ($($path:tt)*) => {
+            ::core::compile_error!(::core::concat!(
+                "cannot resolve `",
+                ::core::stringify!($($path)*),
+                "` in `",
+                ::core::stringify!(#ident),
+                "`"
+            ))
+        };
+    };
+
+    let ident = Ident::new(format!("__ty_{}", ident).as_str(), ident.span());
+    if macros_visibility == DeriveAutoMacrosVisibility::Public {
+        quote! {
+            #[doc(hidden)]
+            #[macro_export]
+            macro_rules! #ident {
+                #rules
+                #fallback
+            }
+        }
+    } else {
+        quote! {
+            #[doc(hidden)]
+            macro_rules! #ident {
+                #rules
+                #fallback
+            }
+        }
+    }
+}
+
+/// Generate the rules that resolve `path` to the given type, and forward any
+/// deeper path to the `__ty_*` macro of an inline type. Types written in the
+/// DSL have no such macro, so paths end there.
+pub(crate) fn generate_ty_rules(path: TokenStream, ty: &TypeFlatten) -> TokenStream {
+    let inner = &ty.inner;
+    if ty.kind != TypeFlattenKind::Static {
+        quote! {
+            (#path) => {
+                #inner
+            };
+            (#path . $($rest:tt)+) => {
+                ::yuuka::ty!(#inner . $($rest)+)
+            };
+        }
+    } else {
+        quote! {
+            (#path) => {
+                #inner
+            };
+        }
+    }
+}
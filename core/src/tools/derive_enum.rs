@@ -1,12 +1,14 @@
 use proc_macro2::TokenStream;
-use std::{cell::RefCell, rc::Rc};
 use syn::{
     braced,
     parse::{Parse, ParseStream},
     Expr, Ident, Token, TypePath,
 };
 
-use super::{DeriveEnumItems, DeriveVisibility, EnumMembers, ExtraMacros, StructName};
+use super::{
+    DeriveEnumItems, DeriveFeatures, DeriveFieldFilter, DeriveSelector, DeriveVisibility,
+    EnumMembers, ExtraMacros, StructName,
+};
 
 #[derive(Debug, Clone)]
 pub struct DeriveEnum {
@@ -18,10 +20,9 @@ pub struct DeriveEnum {
 }
 
 impl DeriveEnum {
-    pub fn pin_unique_id(&self, root_name: String, id: Rc<RefCell<usize>>) -> Self {
+    pub fn pin_name(&self, name: String) -> Self {
         let mut ret = self.clone();
-        ret.ident = ret.ident.pin_unique_id(root_name, *id.borrow());
-        *id.borrow_mut() += 1;
+        ret.ident = ret.ident.pin_name(name);
         ret
     }
 
@@ -37,7 +38,32 @@ impl DeriveEnum {
         ret
     }
 
-    pub fn extend_attr_macros_recursive(&self, extra_macros: Vec<TokenStream>) -> Self {
+    pub fn extend_field_attr_macros_recursive(
+        &self,
+        extra_macros: Vec<(DeriveFieldFilter, TokenStream)>,
+    ) -> Self {
+        let mut ret = self.clone();
+        ret.extra_macros
+            .extend_field_attr_macros_recursive(extra_macros);
+        ret
+    }
+
+    pub fn extend_features(&self, features: DeriveFeatures) -> Self {
+        let mut ret = self.clone();
+        ret.extra_macros.extend_features(features);
+        ret
+    }
+
+    pub fn hoist_attr_macros(&self) -> Self {
+        let mut ret = self.clone();
+        ret.extra_macros.hoist_attr_macros();
+        ret
+    }
+
+    pub fn extend_attr_macros_recursive(
+        &self,
+        extra_macros: Vec<(DeriveSelector, TokenStream)>,
+    ) -> Self {
         let mut ret = self.clone();
         ret.extra_macros.extend_attr_macros_recursive(extra_macros);
         ret
@@ -60,15 +86,20 @@ impl Parse for DeriveEnum {
         };
 
         input.parse::<Token![enum]>()?;
-        let ident: StructName = if input.peek(Ident) {
-            StructName::Named(input.parse()?)
+        let ident: Option<Ident> = if input.peek(Ident) {
+            Some(input.parse()?)
         } else {
-            StructName::Unnamed(None)
+            None
         };
         let content;
-        braced!(content in input);
+        let brace = braced!(content in input);
         let content: DeriveEnumItems = content.parse()?;
 
+        let ident = match ident {
+            Some(ident) => StructName::Named(ident),
+            None => StructName::Unnamed(None, brace.span.join()),
+        };
+
         if input.peek(Token![=]) {
             input.parse::<Token![=]>()?;
             let default_value = input.parse::<Expr>()?;
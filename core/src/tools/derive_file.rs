@@ -0,0 +1,126 @@
+extern crate proc_macro;
+
+use proc_macro2::TokenStream;
+use syn::{
+    parse::{Parse, ParseStream},
+    LitStr, Token,
+};
+
+use crate::{format::lex_error, utils::combine_errors};
+
+/// `derive_struct_file!("path/to/config.yuuka")`, and the same for
+/// `derive_enum_file!`.
+#[derive(Debug, Clone)]
+pub struct DeriveFile {
+    pub path: LitStr,
+}
+
+impl Parse for DeriveFile {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(DeriveFile { path })
+    }
+}
+
+impl DeriveFile {
+    /// Read the file, returning its text and its absolute path.
+    pub fn read(&self) -> syn::Result<(String, String)> {
+        let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+        let path = std::path::Path::new(&root).join(self.path.value());
+        let text = std::fs::read_to_string(&path).map_err(|err| {
+            syn::Error::new(
+                self.path.span(),
+                format!("failed to read `{}`: {}", path.display(), err),
+            )
+        })?;
+
+        Ok((text, path.display().to_string()))
+    }
+
+    /// Tokenize the text of the file and hand it to `lower`, reporting
+    /// errors on the path with their position in the file.
+    pub fn lower<T>(
+        &self,
+        text: &str,
+        lower: impl Fn(TokenStream) -> syn::Result<T>,
+    ) -> syn::Result<T> {
+        lower_text(&self.path.value(), text, lower).map_err(|errors| {
+            combine_errors(
+                errors
+                    .into_iter()
+                    .map(|message| syn::Error::new(self.path.span(), message)),
+            )
+            .unwrap_err()
+        })
+    }
+}
+
+/// Tokenize some DSL text and hand it to `lower`, with every error written as
+/// `name:line:column: message`.
+///
+/// Inside a proc macro, the compiler gives tokens parsed from a string the
+/// span of the macro call, which says nothing about where they are in the
+/// text. So when `lower` fails there, it runs once more on tokens with
+/// proc-macro2's own spans, which do know their line and column. Tokenizing
+/// errors are located by the lexer of the formatter.
+pub(crate) fn lower_text<T>(
+    name: &str,
+    text: &str,
+    lower: impl Fn(TokenStream) -> syn::Result<T>,
+) -> Result<T, Vec<String>> {
+    let tokens = text.parse::<TokenStream>().map_err(|err| {
+        vec![match lex_error(text) {
+            Some(err) => format!("{}:{}", name, err),
+            None => format!("{}: {}", name, err),
+        }]
+    })?;
+    let err = match lower(tokens) {
+        Ok(value) => return Ok(value),
+        Err(err) => err,
+    };
+    if !proc_macro::is_available() {
+        return Err(located_errors(name, err));
+    }
+
+    let _fallback = Fallback::force();
+    let located = text
+        .parse::<TokenStream>()
+        .ok()
+        .and_then(|tokens| lower(tokens).err())
+        .map(|err| located_errors(name, err));
+    Err(located.unwrap_or_else(|| {
+        err.into_iter()
+            .map(|err| format!("{}: {}", name, err))
+            .collect()
+    }))
+}
+
+fn located_errors(name: &str, err: syn::Error) -> Vec<String> {
+    err.into_iter()
+        .map(|err| {
+            let start = err.span().start();
+            format!("{}:{}:{}: {}", name, start.line, start.column + 1, err)
+        })
+        .collect()
+}
+
+/// Keeps proc-macro2 on its own implementation of tokens until dropped, also
+/// when `lower` panics.
+struct Fallback;
+
+impl Fallback {
+    fn force() -> Self {
+        proc_macro2::fallback::force();
+        Fallback
+    }
+}
+
+impl Drop for Fallback {
+    fn drop(&mut self) {
+        proc_macro2::fallback::unforce();
+    }
+}
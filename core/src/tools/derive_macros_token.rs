@@ -0,0 +1,275 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    bracketed, parenthesized,
+    parse::{Parse, ParseStream},
+    token, Ident, LitInt, Token, TypePath,
+};
+
+use super::{
+    yuuka_options::parse_anonymous_naming, DeriveAnonymousNaming, DeriveAutoMacrosVisibility,
+    DeriveFeatures, DeriveFieldFilter, DeriveSelector, YuukaOption, YuukaOptions,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct ExtraDeriveMacros {
+    pub derive_macros: Vec<TypePath>,
+    pub attr_macros: Vec<TokenStream>,
+    pub attr_macros_recursive: Vec<(DeriveSelector, TokenStream)>,
+    pub field_attr_macros_recursive: Vec<(DeriveFieldFilter, TokenStream)>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExtraMacros {
+    pub attr_macros: Vec<TokenStream>,
+    pub derive_macros: Option<ExtraDeriveMacros>,
+    pub macros_visibility: DeriveAutoMacrosVisibility,
+    pub anonymous_naming: DeriveAnonymousNaming,
+    pub features: DeriveFeatures,
+    /// `#[yuuka(proto = 3)]`, only meaningful on fields and variants.
+    pub proto_number: Option<LitInt>,
+}
+
+impl ExtraMacros {
+    pub fn extend_derive_macros(&mut self, other: Vec<TypePath>) {
+        if let Some(derive_macros) = &mut self.derive_macros {
+            derive_macros.derive_macros.extend(other);
+        } else {
+            self.derive_macros = Some(ExtraDeriveMacros {
+                derive_macros: other,
+                attr_macros: vec![],
+                attr_macros_recursive: vec![],
+                field_attr_macros_recursive: vec![],
+            });
+        }
+    }
+
+    pub fn extend_attr_macros(&mut self, other: Vec<TokenStream>) {
+        if let Some(derive_macros) = &mut self.derive_macros {
+            derive_macros.attr_macros.extend(other);
+        } else {
+            self.derive_macros = Some(ExtraDeriveMacros {
+                derive_macros: vec![],
+                attr_macros: other,
+                attr_macros_recursive: vec![],
+                field_attr_macros_recursive: vec![],
+            });
+        }
+    }
+
+    pub fn extend_attr_macros_recursive(&mut self, other: Vec<(DeriveSelector, TokenStream)>) {
+        if let Some(derive_macros) = &mut self.derive_macros {
+            derive_macros.attr_macros_recursive.extend(other);
+        } else {
+            self.derive_macros = Some(ExtraDeriveMacros {
+                derive_macros: vec![],
+                attr_macros: vec![],
+                attr_macros_recursive: other,
+                field_attr_macros_recursive: vec![],
+            });
+        }
+    }
+
+    pub fn extend_field_attr_macros_recursive(
+        &mut self,
+        other: Vec<(DeriveFieldFilter, TokenStream)>,
+    ) {
+        if let Some(derive_macros) = &mut self.derive_macros {
+            derive_macros.field_attr_macros_recursive.extend(other);
+        } else {
+            self.derive_macros = Some(ExtraDeriveMacros {
+                derive_macros: vec![],
+                attr_macros: vec![],
+                attr_macros_recursive: vec![],
+                field_attr_macros_recursive: other,
+            });
+        }
+    }
+
+    pub fn extend_features(&mut self, other: DeriveFeatures) {
+        self.features = self.features.merge(other);
+    }
+
+    /// Treat the attributes placed before `#[derive(...)]` as attributes of
+    /// the type itself, which is what they mean on a root type.
+    pub fn hoist_attr_macros(&mut self) {
+        let attr_macros = std::mem::take(&mut self.attr_macros);
+        if attr_macros.is_empty() {
+            return;
+        }
+
+        if let Some(derive_macros) = &mut self.derive_macros {
+            derive_macros.attr_macros = [attr_macros, derive_macros.attr_macros.clone()].concat();
+        } else {
+            self.derive_macros = Some(ExtraDeriveMacros {
+                derive_macros: vec![],
+                attr_macros,
+                attr_macros_recursive: vec![],
+                field_attr_macros_recursive: vec![],
+            });
+        }
+    }
+}
+
+impl Parse for ExtraMacros {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attr_macros_before_derive = vec![];
+        let mut derive_macros = vec![];
+        let mut attr_macros_after_derive = vec![];
+        let mut attr_macros_after_derive_recursive = vec![];
+        let mut field_attr_macros_recursive = vec![];
+
+        let mut has_export_macro = false;
+        let mut anonymous_naming = DeriveAnonymousNaming::default();
+        let mut features = DeriveFeatures::default();
+        let mut proto_number = None;
+        let mut has_parsed_derive = false;
+        let mut has_type_options = false;
+
+        while input.peek(Token![#]) {
+            input.parse::<Token![#]>()?;
+            let bracked_content;
+            bracketed!(bracked_content in input);
+
+            let head_ident = bracked_content.parse::<Ident>()?;
+            if head_ident == "derive" {
+                if bracked_content.peek(token::Paren) {
+                    let content;
+                    parenthesized!(content in bracked_content);
+
+                    while !content.is_empty() {
+                        let item = content.parse::<TypePath>()?;
+                        derive_macros.push(item);
+
+                        if content.is_empty() {
+                            break;
+                        }
+                        content.parse::<Token![,]>()?;
+                    }
+                }
+
+                has_parsed_derive = true;
+            } else if head_ident == "yuuka" {
+                // Options inside `#[yuuka(...)]` mean the same thing wherever
+                // they are placed.
+                let content;
+                parenthesized!(content in bracked_content);
+
+                for option in content.parse::<YuukaOptions>()?.options {
+                    match option {
+                        YuukaOption::Derive(list) => {
+                            derive_macros.extend(list);
+                            has_type_options = true;
+                        }
+                        YuukaOption::Attr(list) => {
+                            attr_macros_after_derive.extend(list);
+                            has_type_options = true;
+                        }
+                        YuukaOption::Recursive(selector, list) => {
+                            attr_macros_after_derive_recursive
+                                .extend(list.into_iter().map(|item| (selector.clone(), item)));
+                            has_type_options = true;
+                        }
+                        YuukaOption::RecursiveField(filter, list) => {
+                            field_attr_macros_recursive
+                                .extend(list.into_iter().map(|item| (filter, item)));
+                            has_type_options = true;
+                        }
+                        YuukaOption::MacroExport => {
+                            has_export_macro = true;
+                        }
+                        YuukaOption::AnonymousNaming(naming) => {
+                            anonymous_naming = naming;
+                        }
+                        YuukaOption::SerdeDefault => {
+                            features.serde_default = true;
+                        }
+                        YuukaOption::SkipNone => {
+                            features.skip_none = true;
+                        }
+                        YuukaOption::SkipEmpty => {
+                            features.skip_empty = true;
+                        }
+                        YuukaOption::SkipDefault => {
+                            features.skip_default = true;
+                        }
+                        YuukaOption::Partial => {
+                            features.partial = true;
+                        }
+                        YuukaOption::Diff => {
+                            features.diff = true;
+                        }
+                        YuukaOption::Schema => {
+                            features.schema = true;
+                        }
+                        YuukaOption::Example => {
+                            features.example = true;
+                        }
+                        YuukaOption::Proto(number) => {
+                            proto_number = Some(number);
+                        }
+                    }
+                }
+            } else if head_ident == "macros_recursive" {
+                let content;
+                parenthesized!(content in bracked_content);
+
+                let token_stream = content.parse::<TokenStream>()?;
+                attr_macros_after_derive_recursive.push((DeriveSelector::default(), token_stream));
+                has_type_options = true;
+            } else if head_ident == "macro_export" {
+                has_export_macro = true;
+            } else if head_ident == "anonymous_naming" {
+                let content;
+                parenthesized!(content in bracked_content);
+
+                anonymous_naming = parse_anonymous_naming(&content.parse()?)?;
+            } else if !has_parsed_derive {
+                let token_stream = bracked_content.parse::<TokenStream>()?;
+                let token_stream = quote! {
+                    #head_ident #token_stream
+                };
+                attr_macros_before_derive.push(token_stream);
+            } else {
+                let token_stream = bracked_content.parse::<TokenStream>()?;
+                let token_stream = quote! {
+                    #head_ident #token_stream
+                };
+                attr_macros_after_derive.push(token_stream);
+            }
+        }
+
+        if !has_parsed_derive && !has_type_options {
+            Ok(Self {
+                attr_macros: attr_macros_before_derive,
+                derive_macros: None,
+                macros_visibility: if has_export_macro {
+                    DeriveAutoMacrosVisibility::Public
+                } else {
+                    DeriveAutoMacrosVisibility::PublicOnCrate
+                },
+                anonymous_naming,
+                features,
+                proto_number,
+            })
+        } else {
+            Ok(Self {
+                attr_macros: attr_macros_before_derive,
+                derive_macros: Some(ExtraDeriveMacros {
+                    derive_macros,
+                    attr_macros: attr_macros_after_derive,
+                    attr_macros_recursive: attr_macros_after_derive_recursive,
+                    field_attr_macros_recursive,
+                }),
+                macros_visibility: if has_export_macro {
+                    DeriveAutoMacrosVisibility::Public
+                } else {
+                    DeriveAutoMacrosVisibility::PublicOnCrate
+                },
+                anonymous_naming,
+                features,
+                proto_number,
+            })
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Attribute, Ident, LitStr, Token,
+};
+
+use super::DeriveStruct;
+
+/// `derive_struct_from_json!(#[derive(...)] pub Root, "path/to/sample.json")`,
+/// and the same for `derive_struct_from_json_schema!`.
+#[derive(Debug, Clone)]
+pub struct DeriveStructFromJson {
+    pub attrs: Vec<Attribute>,
+    pub visibility: Option<Token![pub]>,
+    pub ident: Ident,
+    pub path: LitStr,
+}
+
+impl Parse for DeriveStructFromJson {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let visibility = input.parse()?;
+        let ident = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let path = input.parse()?;
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(DeriveStructFromJson {
+            attrs,
+            visibility,
+            ident,
+            path,
+        })
+    }
+}
+
+impl DeriveStructFromJson {
+    /// Read the JSON file and write out the fields it describes in the DSL
+    /// with `items`, returning the parsed struct and the absolute path of the
+    /// file.
+    ///
+    /// `items` is also told whether the derive list mentions serde, since
+    /// keys that are not valid field names can only be kept with serde.
+    pub fn to_derive_struct(
+        &self,
+        items: impl FnOnce(&Ident, &serde_json::Value, bool) -> Result<TokenStream, String>,
+    ) -> syn::Result<(DeriveStruct, String)> {
+        let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+        let path = std::path::Path::new(&root).join(self.path.value());
+        let text = std::fs::read_to_string(&path).map_err(|err| {
+            syn::Error::new(
+                self.path.span(),
+                format!("failed to read `{}`: {}", path.display(), err),
+            )
+        })?;
+        let value = serde_json::from_str::<serde_json::Value>(&text).map_err(|err| {
+            syn::Error::new(
+                self.path.span(),
+                format!("failed to parse `{}`: {}", path.display(), err),
+            )
+        })?;
+
+        let attrs = &self.attrs;
+        let has_serde = mentions_serde(quote! { #(#attrs)* });
+        let items = items(&self.ident, &value, has_serde)
+            .map_err(|message| syn::Error::new(self.path.span(), message))?;
+
+        let visibility = &self.visibility;
+        let ident = &self.ident;
+        let input = syn::parse2::<DeriveStruct>(quote! {
+            #(#attrs)*
+            #visibility #ident {
+                #items
+            }
+        })?;
+
+        Ok((input, path.display().to_string()))
+    }
+}
+
+fn mentions_serde(tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "Serialize" || ident == "Deserialize",
+        TokenTree::Group(group) => mentions_serde(group.stream()),
+        _ => false,
+    })
+}
@@ -0,0 +1,328 @@
+use proc_macro2::{Span, TokenStream};
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use syn::{Expr, Ident, TypePath};
+
+use crate::utils::glob_match;
+
+pub(crate) mod auto_macros;
+pub(crate) mod derive_enum;
+pub(crate) mod derive_enum_items;
+pub(crate) mod derive_file;
+pub(crate) mod derive_macros_token;
+pub(crate) mod derive_struct;
+pub(crate) mod derive_struct_from_json;
+pub(crate) mod derive_struct_items;
+pub(crate) mod ty_macros;
+pub(crate) mod yuuka_options;
+
+pub(crate) use auto_macros::AutoMacros;
+pub(crate) use derive_enum::DeriveEnum;
+pub(crate) use derive_enum_items::DeriveEnumItems;
+pub(crate) use derive_file::{lower_text, DeriveFile};
+pub(crate) use derive_macros_token::ExtraMacros;
+pub(crate) use derive_struct::DeriveStruct;
+pub(crate) use derive_struct_from_json::DeriveStructFromJson;
+pub(crate) use derive_struct_items::DeriveStructItems;
+pub(crate) use ty_macros::TyMacros;
+pub(crate) use yuuka_options::{YuukaOption, YuukaOptions};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DeriveVisibility {
+    Public,
+    #[default]
+    PublicOnCrate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DeriveAutoMacrosVisibility {
+    Public,
+    #[default]
+    PublicOnCrate,
+}
+
+/// How anonymous inline types are named.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DeriveAnonymousNaming {
+    /// `_Root_0_anonymous`, numbered in definition order.
+    #[default]
+    Counter,
+    /// `Root_NickName_Chinese`, built from the field path.
+    Path,
+    /// `RootNickNameChinese`, built from the field path.
+    PathCamel,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum DefaultValue {
+    None,
+    Single(Box<Expr>),
+    Array(Vec<Expr>),
+}
+
+/// The name of a struct or enum in the DSL.
+///
+/// Anonymous types keep the span of their braces, so that diagnostics and
+/// go-to-definition on the generated name land on the inline definition.
+#[derive(Debug, Clone)]
+pub(crate) enum StructName {
+    Named(Ident),
+    Unnamed(Option<String>, Span),
+}
+
+impl StructName {
+    pub(crate) fn to_ident(&self) -> syn::Result<Ident> {
+        Ok(match self {
+            StructName::Named(v) => v.clone(),
+            StructName::Unnamed(Some(v), span) => Ident::new(v, *span),
+            StructName::Unnamed(None, span) => {
+                return Err(syn::Error::new(*span, "Unnamed struct is not supported"))
+            }
+        })
+    }
+
+    pub(crate) fn pin_name(&self, name: String) -> Self {
+        match self {
+            StructName::Named(v) => StructName::Named(v.clone()),
+            StructName::Unnamed(v, span) => {
+                if let Some(v) = v {
+                    StructName::Unnamed(Some(v.clone()), *span)
+                } else {
+                    StructName::Unnamed(Some(name), *span)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum StructType {
+    Static(TypePath),
+    InlineStruct(Box<DeriveStruct>),
+    InlineEnum(Box<DeriveEnum>),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum EnumValue {
+    Empty,
+    Tuple(Vec<(StructType, ExtraTypeWrapper)>),
+    Struct(StructMembers),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ExtraTypeWrapper {
+    Default,
+    Vec,
+    Option,
+    OptionVec,
+}
+
+/// Which fields a recursive field attribute applies to, by the outermost
+/// type of the field, whether it comes from `?` / `[...]` or is written out.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DeriveFieldFilter {
+    #[default]
+    All,
+    /// Fields that are neither `Option<T>` nor `Vec<T>`.
+    Plain,
+    /// `Option<T>` fields, including `Option<Vec<T>>`.
+    Option,
+    /// `Vec<T>` fields, including `Option<Vec<T>>`.
+    Vec,
+}
+
+impl DeriveFieldFilter {
+    pub(crate) fn matches(&self, ty: &TypePath) -> bool {
+        let is_option = outer_ident_is(ty, "Option");
+        let is_vec = outer_ident_is(ty, "Vec")
+            || (is_option && option_inner(ty).is_some_and(|ty| outer_ident_is(ty, "Vec")));
+
+        match self {
+            DeriveFieldFilter::All => true,
+            DeriveFieldFilter::Plain => !is_option && !is_vec,
+            DeriveFieldFilter::Option => is_option,
+            DeriveFieldFilter::Vec => is_vec,
+        }
+    }
+}
+
+fn outer_ident_is(ty: &TypePath, name: &str) -> bool {
+    ty.qself.is_none()
+        && ty
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == name)
+}
+
+fn option_inner(ty: &TypePath) -> Option<&TypePath> {
+    match &ty.path.segments.last()?.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(syn::Type::Path(ty)) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Opt-in code generation enabled through `#[yuuka(...)]`. A feature turned
+/// on for a type is also turned on for every type nested in it.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeriveFeatures {
+    /// `serde_default`, honour DSL default values during deserialization.
+    pub serde_default: bool,
+    /// `skip_none`, skip `None` in `?` fields when serializing.
+    pub skip_none: bool,
+    /// `skip_empty`, skip empty `Vec`s in `[...]` fields when serializing.
+    pub skip_empty: bool,
+    /// `skip_default`, skip fields equal to their DSL default when serializing.
+    pub skip_default: bool,
+    /// `partial`, generate a `{Ident}Partial` twin for every struct.
+    pub partial: bool,
+    /// `diff`, generate a `diff` method listing the changed leaves.
+    pub diff: bool,
+    /// `schema`, implement `yuuka::Schema` with a static description.
+    pub schema: bool,
+    /// `example`, generate an `example` constructor with every field filled.
+    pub example: bool,
+}
+
+impl DeriveFeatures {
+    pub(crate) fn merge(self, other: Self) -> Self {
+        Self {
+            serde_default: self.serde_default || other.serde_default,
+            skip_none: self.skip_none || other.skip_none,
+            skip_empty: self.skip_empty || other.skip_empty,
+            skip_default: self.skip_default || other.skip_default,
+            partial: self.partial || other.partial,
+            diff: self.diff || other.diff,
+            schema: self.schema || other.schema,
+            example: self.example || other.example,
+        }
+    }
+}
+
+/// The kind of generated type a [`DeriveSelector`] can ask for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeriveSelectorKind {
+    Struct,
+    Enum,
+}
+
+/// Which generated types a recursive attribute applies to. Every condition
+/// that is set must hold; the default selects every type.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DeriveSelector {
+    pub kind: Option<DeriveSelectorKind>,
+    /// Only anonymous types (`Some(true)`) or only named ones (`Some(false)`).
+    pub anonymous: Option<bool>,
+    /// Distance from the root type, which is at depth 0.
+    pub depth: Option<usize>,
+    /// A glob over the generated type name, with `*` and `?` wildcards.
+    pub name: Option<String>,
+}
+
+impl DeriveSelector {
+    pub(crate) fn matches(
+        &self,
+        kind: DeriveSelectorKind,
+        anonymous: bool,
+        depth: usize,
+        name: &str,
+    ) -> bool {
+        self.kind.map_or(true, |v| v == kind)
+            && self.anonymous.map_or(true, |v| v == anonymous)
+            && self.depth.map_or(true, |v| v == depth)
+            && self
+                .name
+                .as_ref()
+                .map_or(true, |pattern| glob_match(pattern, name))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ExtraMacrosFlatten {
+    pub(crate) derive_macros: Vec<TypePath>,
+    pub(crate) attr_macros: Vec<TokenStream>,
+    pub(crate) features: DeriveFeatures,
+    /// Whether the type was declared without a name.
+    pub(crate) anonymous: bool,
+}
+
+pub(crate) type StructMembers = Vec<(
+    Ident,
+    StructType,
+    ExtraTypeWrapper,
+    DefaultValue,
+    ExtraMacros,
+)>;
+pub(crate) type EnumMembers = Vec<(Ident, EnumValue, ExtraMacros)>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TypeFlattenKind {
+    Static,
+    InlineStruct,
+    InlineEnum,
+}
+
+/// A field type after flattening.
+///
+/// `ty` is the full type written into the generated code (wrappers included),
+/// while `inner` is the type as written in the DSL, or the generated type for
+/// inline structs and enums. `wrapper` records the `?` / `[...]` around it.
+#[derive(Debug, Clone)]
+pub(crate) struct TypeFlatten {
+    pub(crate) ty: TypePath,
+    pub(crate) inner: TypePath,
+    pub(crate) kind: TypeFlattenKind,
+    pub(crate) wrapper: ExtraTypeWrapper,
+    /// The protobuf field number set on the field with `#[yuuka(proto = 3)]`.
+    pub(crate) proto_number: Option<u32>,
+    /// Whether this is an inline enum with a default variant, the only kind
+    /// of inline enum that implements `Default`.
+    pub(crate) enum_default: bool,
+}
+
+impl TypeFlatten {
+    /// Whether `serde_default` can fill in the field when it has no DSL
+    /// default: inline structs and inline types behind `?` or `[...]` always
+    /// implement `Default`, inline enums only with a default variant.
+    pub(crate) fn inline_default(&self) -> bool {
+        match self.kind {
+            TypeFlattenKind::Static => false,
+            TypeFlattenKind::InlineStruct => true,
+            TypeFlattenKind::InlineEnum => {
+                self.enum_default || self.wrapper != ExtraTypeWrapper::Default
+            }
+        }
+    }
+}
+
+impl ToTokens for TypeFlatten {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.ty.to_tokens(tokens);
+    }
+}
+
+pub(crate) type StructMembersFlatten = Vec<(Ident, TypeFlatten, DefaultValue, Vec<TokenStream>)>;
+
+#[derive(Debug, Clone)]
+pub(crate) enum EnumValueFlatten {
+    Empty,
+    Tuple(Vec<TypeFlatten>),
+    Struct(StructMembersFlatten),
+}
+pub(crate) type StructsFlatten = Vec<(Ident, StructMembersFlatten, ExtraMacrosFlatten)>;
+pub(crate) type EnumsFlatten = Vec<(
+    Ident,
+    Vec<(Ident, EnumValueFlatten, Option<u32>, Vec<TokenStream>)>,
+    DefaultValue,
+    ExtraMacrosFlatten,
+)>;
+
+#[derive(Debug, Clone)]
+pub(crate) enum DeriveBox {
+    Struct(Box<DeriveStruct>),
+    Enum(Box<DeriveEnum>),
+}
@@ -0,0 +1,35 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{
+    parse::{Parse, ParseStream},
+    Ident, LitInt, Token,
+};
+
+#[derive(Debug, Clone)]
+pub struct TyMacros {
+    pub ident: Ident,
+    pub path: Vec<TokenStream>,
+}
+
+impl Parse for TyMacros {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse()?;
+
+        let mut path = vec![];
+        while !input.is_empty() {
+            input.parse::<Token![.]>()?;
+
+            if input.peek(LitInt) {
+                // Root.key.0
+                let index = input.parse::<LitInt>()?;
+                path.push(index.into_token_stream());
+            } else {
+                // Root.key.key
+                let key = input.parse::<Ident>()?;
+                path.push(key.into_token_stream());
+            }
+        }
+
+        Ok(TyMacros { ident, path })
+    }
+}
@@ -0,0 +1,254 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{
+    ext::IdentExt,
+    parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Ident, LitInt, LitStr, Meta, Token, TypePath,
+};
+
+use super::{DeriveAnonymousNaming, DeriveFieldFilter, DeriveSelector, DeriveSelectorKind};
+
+/// A single option inside `#[yuuka(...)]`.
+#[derive(Debug, Clone)]
+pub enum YuukaOption {
+    /// `derive(Serialize, Deserialize)`, derive macros for the generated type.
+    Derive(Vec<TypePath>),
+    /// `attr(serde(rename_all = "camelCase"))`, attributes for the generated type.
+    Attr(Vec<TokenStream>),
+    /// `recursive(kind = enum, serde(tag = "type"))`, attributes for the
+    /// generated type and every type nested in it, optionally limited to the
+    /// types picked by the selector.
+    Recursive(DeriveSelector, Vec<TokenStream>),
+    /// `recursive_field(wrapper = option, serde(default))`, attributes for
+    /// every field of the generated type and of every type nested in it,
+    /// optionally limited to fields with the given wrapper.
+    RecursiveField(DeriveFieldFilter, Vec<TokenStream>),
+    /// `macro_export`, export the helper macros used by `auto!` and `ty!`.
+    MacroExport,
+    /// `anonymous_naming = path`, how anonymous types are named.
+    AnonymousNaming(DeriveAnonymousNaming),
+    /// `serde_default`, fill fields missing from the input with their DSL
+    /// default values when deserializing.
+    SerdeDefault,
+    /// `skip_none`, skip `None` in `?` fields when serializing.
+    SkipNone,
+    /// `skip_empty`, skip empty `Vec`s in `[...]` fields when serializing.
+    SkipEmpty,
+    /// `skip_default`, skip fields equal to their DSL default when serializing.
+    SkipDefault,
+    /// `partial`, generate a `{Ident}Partial` twin with every field optional.
+    Partial,
+    /// `diff`, generate a `diff` method listing the changed leaves.
+    Diff,
+    /// `schema`, implement `yuuka::Schema` with a static description.
+    Schema,
+    /// `example`, generate an `example` constructor with every field filled.
+    Example,
+    /// `proto = 3`, the protobuf number of a field or variant.
+    Proto(LitInt),
+}
+
+/// The content of a `#[yuuka(...)]` attribute.
+#[derive(Debug, Clone)]
+pub struct YuukaOptions {
+    pub options: Vec<YuukaOption>,
+}
+
+const KNOWN_OPTIONS: &str = "`derive`, `attr`, `recursive`, `recursive_field`, `macro_export`, \
+    `anonymous_naming`, `serde_default`, `skip_none`, `skip_empty`, `skip_default`, `partial`, `diff`, `schema`, `example`, `proto`";
+
+impl Parse for YuukaOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = vec![];
+
+        while !input.is_empty() {
+            let key = input.parse::<Ident>()?;
+
+            if key == "derive" {
+                // derive(Sth, ...)
+                let content;
+                parenthesized!(content in input);
+                let list = Punctuated::<TypePath, Token![,]>::parse_terminated(&content)?;
+                options.push(YuukaOption::Derive(list.into_iter().collect()));
+            } else if key == "attr" {
+                // attr(sth(...), ...)
+                options.push(YuukaOption::Attr(parse_meta_list(input)?));
+            } else if key == "recursive" {
+                // recursive(kind = enum, sth(...), ...)
+                let content;
+                parenthesized!(content in input);
+
+                let mut selector = DeriveSelector::default();
+                let mut list = vec![];
+                while !content.is_empty() {
+                    if !parse_selector(&content, &mut selector)? {
+                        list.push(content.parse::<Meta>()?.into_token_stream());
+                    }
+
+                    if content.is_empty() {
+                        break;
+                    }
+                    content.parse::<Token![,]>()?;
+                }
+
+                options.push(YuukaOption::Recursive(selector, list));
+            } else if key == "recursive_field" {
+                // recursive_field(wrapper = option, sth(...), ...)
+                let content;
+                parenthesized!(content in input);
+
+                let mut filter = DeriveFieldFilter::All;
+                let mut list = vec![];
+                while !content.is_empty() {
+                    if content.peek2(Token![=])
+                        && content
+                            .fork()
+                            .parse::<Ident>()
+                            .is_ok_and(|ident| ident == "wrapper")
+                    {
+                        content.parse::<Ident>()?;
+                        content.parse::<Token![=]>()?;
+                        filter = parse_field_filter(&content.parse()?)?;
+                    } else {
+                        list.push(content.parse::<Meta>()?.into_token_stream());
+                    }
+
+                    if content.is_empty() {
+                        break;
+                    }
+                    content.parse::<Token![,]>()?;
+                }
+
+                options.push(YuukaOption::RecursiveField(filter, list));
+            } else if key == "serde_default" {
+                options.push(YuukaOption::SerdeDefault);
+            } else if key == "skip_none" {
+                options.push(YuukaOption::SkipNone);
+            } else if key == "skip_empty" {
+                options.push(YuukaOption::SkipEmpty);
+            } else if key == "skip_default" {
+                options.push(YuukaOption::SkipDefault);
+            } else if key == "partial" {
+                options.push(YuukaOption::Partial);
+            } else if key == "diff" {
+                options.push(YuukaOption::Diff);
+            } else if key == "schema" {
+                options.push(YuukaOption::Schema);
+            } else if key == "example" {
+                options.push(YuukaOption::Example);
+            } else if key == "proto" {
+                // proto = 3
+                input.parse::<Token![=]>()?;
+                options.push(YuukaOption::Proto(input.parse()?));
+            } else if key == "macro_export" {
+                options.push(YuukaOption::MacroExport);
+            } else if key == "anonymous_naming" {
+                // anonymous_naming = path
+                input.parse::<Token![=]>()?;
+                options.push(YuukaOption::AnonymousNaming(parse_anonymous_naming(
+                    &input.parse()?,
+                )?));
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!(
+                        "unknown yuuka option `{}`; expected one of {}",
+                        key, KNOWN_OPTIONS
+                    ),
+                ));
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(YuukaOptions { options })
+    }
+}
+
+fn parse_meta_list(input: ParseStream) -> syn::Result<Vec<TokenStream>> {
+    let content;
+    parenthesized!(content in input);
+    let list = Punctuated::<Meta, Token![,]>::parse_terminated(&content)?;
+
+    Ok(list
+        .into_iter()
+        .map(|meta| meta.into_token_stream())
+        .collect())
+}
+
+pub(crate) fn parse_anonymous_naming(strategy: &Ident) -> syn::Result<DeriveAnonymousNaming> {
+    if strategy == "counter" {
+        Ok(DeriveAnonymousNaming::Counter)
+    } else if strategy == "path" {
+        Ok(DeriveAnonymousNaming::Path)
+    } else if strategy == "path_camel" {
+        Ok(DeriveAnonymousNaming::PathCamel)
+    } else {
+        Err(syn::Error::new(
+            strategy.span(),
+            "unknown anonymous naming strategy; expected `counter`, `path` or `path_camel`",
+        ))
+    }
+}
+
+fn parse_field_filter(wrapper: &Ident) -> syn::Result<DeriveFieldFilter> {
+    if wrapper == "plain" {
+        Ok(DeriveFieldFilter::Plain)
+    } else if wrapper == "option" {
+        Ok(DeriveFieldFilter::Option)
+    } else if wrapper == "vec" {
+        Ok(DeriveFieldFilter::Vec)
+    } else {
+        Err(syn::Error::new(
+            wrapper.span(),
+            "unknown field wrapper; expected `plain`, `option` or `vec`",
+        ))
+    }
+}
+
+/// Parse one selector item of `recursive(...)` into `selector`, returning
+/// `false` without consuming anything if the next item is an attribute.
+fn parse_selector(input: ParseStream, selector: &mut DeriveSelector) -> syn::Result<bool> {
+    let fork = input.fork();
+    let Ok(key) = fork.call(Ident::parse_any) else {
+        return Ok(false);
+    };
+    let is_flag = fork.is_empty() || fork.peek(Token![,]);
+    let is_pair = fork.peek(Token![=]);
+
+    if is_flag && (key == "anonymous" || key == "named") {
+        input.parse::<Ident>()?;
+        selector.anonymous = Some(key == "anonymous");
+    } else if is_pair && key == "kind" {
+        input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+        let kind = input.call(Ident::parse_any)?;
+        selector.kind = Some(if kind == "struct" {
+            DeriveSelectorKind::Struct
+        } else if kind == "enum" {
+            DeriveSelectorKind::Enum
+        } else {
+            return Err(syn::Error::new(
+                kind.span(),
+                "unknown type kind; expected `struct` or `enum`",
+            ));
+        });
+    } else if is_pair && key == "depth" {
+        input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+        selector.depth = Some(input.parse::<LitInt>()?.base10_parse()?);
+    } else if is_pair && key == "name" {
+        input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+        selector.name = Some(input.parse::<LitStr>()?.value());
+    } else {
+        return Ok(false);
+    }
+
+    Ok(true)
+}
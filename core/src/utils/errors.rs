@@ -0,0 +1,12 @@
+/// Fold every collected error into a single `syn::Error`, so that all of them
+/// are reported at once instead of only the first one.
+pub(crate) fn combine_errors(errors: impl IntoIterator<Item = syn::Error>) -> syn::Result<()> {
+    let mut errors = errors.into_iter();
+    match errors.next() {
+        Some(mut first) => {
+            first.extend(errors);
+            Err(first)
+        }
+        None => Ok(()),
+    }
+}
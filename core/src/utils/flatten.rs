@@ -0,0 +1,418 @@
+use proc_macro2::TokenStream;
+use std::{cell::RefCell, rc::Rc};
+use syn::{parse_quote, TypePath};
+
+use super::{combine_errors, naming::anonymous_name};
+use crate::tools::{
+    DefaultValue, DeriveAnonymousNaming, DeriveBox, DeriveSelectorKind, EnumValue,
+    EnumValueFlatten, EnumsFlatten, ExtraMacros, ExtraMacrosFlatten, ExtraTypeWrapper,
+    StructMembers, StructMembersFlatten, StructName, StructType, StructsFlatten, TypeFlatten,
+    TypeFlattenKind,
+};
+
+/// State shared by every level of a single `flatten` run.
+#[derive(Debug, Clone)]
+pub(crate) struct FlattenContext {
+    pub(crate) root_name: String,
+    pub(crate) unique_id_count: Rc<RefCell<usize>>,
+    pub(crate) naming: DeriveAnonymousNaming,
+    /// How far the type being flattened is from the root type.
+    pub(crate) depth: usize,
+}
+
+impl FlattenContext {
+    pub(crate) fn new(root_name: String, naming: DeriveAnonymousNaming) -> Self {
+        Self {
+            root_name,
+            unique_id_count: Rc::new(RefCell::new(0)),
+            naming,
+            depth: 0,
+        }
+    }
+
+    fn nested(&self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            ..self.clone()
+        }
+    }
+
+    fn next_name(&self, parent_name: &str, path: &[String]) -> String {
+        let unique_id = *self.unique_id_count.borrow();
+        *self.unique_id_count.borrow_mut() += 1;
+
+        anonymous_name(self.naming, &self.root_name, unique_id, parent_name, path)
+    }
+}
+
+pub(crate) fn flatten(
+    context: &FlattenContext,
+    parent: DeriveBox,
+) -> syn::Result<(StructsFlatten, EnumsFlatten)> {
+    match parent {
+        DeriveBox::Struct(parent) => {
+            let parent_name = parent.ident.to_ident()?.to_string();
+            let (items, mut structs, enums) = flatten_members(
+                context,
+                &parent_name,
+                &[],
+                &parent.extra_macros,
+                &parent.items,
+            )?;
+
+            let ty = parent.ident.to_ident()?;
+            let extra_macros = flatten_extra_macros(
+                context,
+                &parent.extra_macros,
+                DeriveSelectorKind::Struct,
+                &parent.ident,
+            )?;
+            structs.push((ty, items, extra_macros));
+
+            Ok((structs, enums))
+        }
+        DeriveBox::Enum(parent) => {
+            let parent_name = parent.ident.to_ident()?.to_string();
+            let mut structs = vec![];
+            let mut enums = vec![];
+
+            let mut items = vec![];
+            let mut errors = vec![];
+            for (key, value, extra_macros) in parent.items.iter() {
+                let proto_number = match proto_number(extra_macros) {
+                    Ok(v) => v,
+                    Err(err) => {
+                        errors.push(err);
+                        continue;
+                    }
+                };
+                match value {
+                    EnumValue::Empty => {
+                        items.push((
+                            key.clone(),
+                            EnumValueFlatten::Empty,
+                            proto_number,
+                            extra_macros.attr_macros.clone(),
+                        ));
+                    }
+                    EnumValue::Tuple(v) => {
+                        let mut tuple = vec![];
+                        for (index, (ty, extra_type_wrapper)) in v.iter().enumerate() {
+                            let path = if v.len() == 1 {
+                                vec![key.to_string()]
+                            } else {
+                                vec![key.to_string(), index.to_string()]
+                            };
+                            let (ty, sub_structs, sub_enums) = match flatten_type(
+                                context,
+                                &parent_name,
+                                &path,
+                                &parent.extra_macros,
+                                ty,
+                                extra_type_wrapper,
+                                extra_macros,
+                            ) {
+                                Ok(v) => v,
+                                Err(err) => {
+                                    errors.push(err);
+                                    continue;
+                                }
+                            };
+
+                            structs.extend(sub_structs);
+                            enums.extend(sub_enums);
+                            tuple.push(ty);
+                        }
+                        items.push((
+                            key.clone(),
+                            EnumValueFlatten::Tuple(tuple),
+                            proto_number,
+                            extra_macros.attr_macros.clone(),
+                        ));
+                    }
+                    EnumValue::Struct(v) => {
+                        let (sub_items, sub_structs, sub_enums) = match flatten_members(
+                            context,
+                            &parent_name,
+                            &[key.to_string()],
+                            &parent.extra_macros,
+                            v,
+                        ) {
+                            Ok(v) => v,
+                            Err(err) => {
+                                errors.push(err);
+                                continue;
+                            }
+                        };
+
+                        structs.extend(sub_structs);
+                        enums.extend(sub_enums);
+                        items.push((
+                            key.clone(),
+                            EnumValueFlatten::Struct(sub_items),
+                            proto_number,
+                            extra_macros.attr_macros.clone(),
+                        ));
+                    }
+                }
+            }
+
+            combine_errors(errors)?;
+
+            let ty = parent.ident.to_ident()?;
+            let extra_macros = flatten_extra_macros(
+                context,
+                &parent.extra_macros,
+                DeriveSelectorKind::Enum,
+                &parent.ident,
+            )?;
+            enums.push((
+                ty,
+                items,
+                if let Some(value) = parent.default_value.clone() {
+                    DefaultValue::Single(parse_quote! { Self::#value })
+                } else {
+                    DefaultValue::None
+                },
+                extra_macros,
+            ));
+
+            Ok((structs, enums))
+        }
+    }
+}
+
+/// Merge the type-level attributes of a generated type, keeping only the
+/// recursive ones whose selector picks this type.
+fn flatten_extra_macros(
+    context: &FlattenContext,
+    extra_macros: &ExtraMacros,
+    kind: DeriveSelectorKind,
+    ident: &StructName,
+) -> syn::Result<ExtraMacrosFlatten> {
+    let anonymous = matches!(ident, StructName::Unnamed(..));
+    let Some(derive_macros) = extra_macros.derive_macros.clone() else {
+        return Ok(ExtraMacrosFlatten {
+            derive_macros: vec![],
+            attr_macros: vec![],
+            features: extra_macros.features,
+            anonymous,
+        });
+    };
+
+    let name = ident.to_ident()?.to_string();
+    let attr_macros_recursive = derive_macros
+        .attr_macros_recursive
+        .into_iter()
+        .filter(|(selector, _)| selector.matches(kind, anonymous, context.depth, &name))
+        .map(|(_, item)| item);
+
+    Ok(ExtraMacrosFlatten {
+        derive_macros: derive_macros.derive_macros,
+        attr_macros: derive_macros
+            .attr_macros
+            .into_iter()
+            .chain(attr_macros_recursive)
+            .collect(),
+        features: extra_macros.features,
+        anonymous,
+    })
+}
+
+/// The recursive field attributes of `parent_extra_macros` that apply to a
+/// field of the given type.
+fn field_attr_macros(parent_extra_macros: &ExtraMacros, ty: &TypePath) -> Vec<TokenStream> {
+    parent_extra_macros
+        .derive_macros
+        .as_ref()
+        .map(|derive_macros| {
+            derive_macros
+                .field_attr_macros_recursive
+                .iter()
+                .filter(|(filter, _)| filter.matches(ty))
+                .map(|(_, item)| item.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The number set with `#[yuuka(proto = 3)]` on a field or variant.
+fn proto_number(extra_macros: &ExtraMacros) -> syn::Result<Option<u32>> {
+    extra_macros
+        .proto_number
+        .as_ref()
+        .map(|v| v.base10_parse())
+        .transpose()
+}
+
+fn flatten_members(
+    context: &FlattenContext,
+    parent_name: &str,
+    path: &[String],
+    parent_extra_macros: &ExtraMacros,
+    members: &StructMembers,
+) -> syn::Result<(StructMembersFlatten, StructsFlatten, EnumsFlatten)> {
+    let mut structs = vec![];
+    let mut enums = vec![];
+
+    let mut items = vec![];
+    let mut errors = vec![];
+    for (key, ty, extra_type_wrapper, default_value, extra_macros) in members.iter() {
+        let path = [path, &[key.to_string()]].concat();
+        let (mut ty, sub_structs, sub_enums) = match flatten_type(
+            context,
+            parent_name,
+            &path,
+            parent_extra_macros,
+            ty,
+            extra_type_wrapper,
+            extra_macros,
+        ) {
+            Ok(v) => v,
+            Err(err) => {
+                errors.push(err);
+                continue;
+            }
+        };
+        ty.proto_number = match proto_number(extra_macros) {
+            Ok(v) => v,
+            Err(err) => {
+                errors.push(err);
+                continue;
+            }
+        };
+
+        let attr_macros = extra_macros
+            .attr_macros
+            .iter()
+            .cloned()
+            .chain(field_attr_macros(parent_extra_macros, &ty.ty))
+            .collect();
+
+        structs.extend(sub_structs);
+        enums.extend(sub_enums);
+        items.push((key.clone(), ty, default_value.clone(), attr_macros));
+    }
+    combine_errors(errors)?;
+
+    Ok((items, structs, enums))
+}
+
+fn flatten_type(
+    context: &FlattenContext,
+    parent_name: &str,
+    path: &[String],
+    parent_extra_macros: &ExtraMacros,
+    ty: &StructType,
+    extra_type_wrapper: &ExtraTypeWrapper,
+    extra_macros: &ExtraMacros,
+) -> syn::Result<(TypeFlatten, StructsFlatten, EnumsFlatten)> {
+    let (inner, kind, enum_default, structs, enums) = match ty {
+        StructType::Static(v) => {
+            let ty = match extra_type_wrapper {
+                ExtraTypeWrapper::Option => parse_quote! { Option<#v> },
+                ExtraTypeWrapper::OptionVec => parse_quote! { Option<Vec<#v>> },
+                _ => v.clone(),
+            };
+
+            return Ok((
+                TypeFlatten {
+                    ty,
+                    inner: v.clone(),
+                    kind: TypeFlattenKind::Static,
+                    wrapper: *extra_type_wrapper,
+                    proto_number: None,
+                    enum_default: false,
+                },
+                vec![],
+                vec![],
+            ));
+        }
+        StructType::InlineStruct(v) => {
+            let v = v.pin_name(context.next_name(parent_name, path));
+            // The field's derive macros and recursive attributes were already
+            // applied while parsing, so only its plain attributes are left.
+            let v = if let Some(derive_macros) = extra_macros.derive_macros.clone() {
+                v.extend_attr_macros(derive_macros.attr_macros)
+                    .extend_field_attr_macros_recursive(derive_macros.field_attr_macros_recursive)
+            } else {
+                v
+            };
+            let v = v
+                .extend_features(extra_macros.features)
+                .extend_features(parent_extra_macros.features);
+            let v = if let Some(derive_macros) = parent_extra_macros.derive_macros.clone() {
+                v.extend_derive_macros(derive_macros.derive_macros)
+                    .extend_attr_macros_recursive(derive_macros.attr_macros_recursive)
+                    .extend_field_attr_macros_recursive(derive_macros.field_attr_macros_recursive)
+            } else {
+                v
+            };
+
+            let (structs, enums) =
+                flatten(&context.nested(), DeriveBox::Struct(Box::new(v.clone())))?;
+
+            let ty = v.ident.to_ident()?;
+            (
+                parse_quote! { #ty },
+                TypeFlattenKind::InlineStruct,
+                false,
+                structs,
+                enums,
+            )
+        }
+        StructType::InlineEnum(v) => {
+            let v = v.pin_name(context.next_name(parent_name, path));
+            // The field's derive macros and recursive attributes were already
+            // applied while parsing, so only its plain attributes are left.
+            let v = if let Some(derive_macros) = extra_macros.derive_macros.clone() {
+                v.extend_attr_macros(derive_macros.attr_macros)
+                    .extend_field_attr_macros_recursive(derive_macros.field_attr_macros_recursive)
+            } else {
+                v
+            };
+            let v = v
+                .extend_features(extra_macros.features)
+                .extend_features(parent_extra_macros.features);
+            let v = if let Some(derive_macros) = parent_extra_macros.derive_macros.clone() {
+                v.extend_derive_macros(derive_macros.derive_macros)
+                    .extend_attr_macros_recursive(derive_macros.attr_macros_recursive)
+                    .extend_field_attr_macros_recursive(derive_macros.field_attr_macros_recursive)
+            } else {
+                v
+            };
+
+            let (structs, enums) =
+                flatten(&context.nested(), DeriveBox::Enum(Box::new(v.clone())))?;
+
+            let ty = v.ident.to_ident()?;
+            (
+                parse_quote! { #ty },
+                TypeFlattenKind::InlineEnum,
+                v.default_value.is_some(),
+                structs,
+                enums,
+            )
+        }
+    };
+
+    let ty = match extra_type_wrapper {
+        ExtraTypeWrapper::Default => parse_quote! { #inner },
+        ExtraTypeWrapper::Vec => parse_quote! { Vec<#inner> },
+        ExtraTypeWrapper::Option => parse_quote! { Option<#inner> },
+        ExtraTypeWrapper::OptionVec => parse_quote! { Option<Vec<#inner>> },
+    };
+
+    Ok((
+        TypeFlatten {
+            ty,
+            inner,
+            kind,
+            wrapper: *extra_type_wrapper,
+            proto_number: None,
+            enum_default,
+        },
+        structs,
+        enums,
+    ))
+}
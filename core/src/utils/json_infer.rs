@@ -0,0 +1,235 @@
+use std::collections::HashSet;
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use serde_json::Value;
+use syn::Ident;
+
+use super::{key_field_ident, key_field_name, key_type_name, singular, unique_name};
+
+/// The shape of the values seen at one position of a JSON sample.
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    /// Nothing was seen, like the items of an empty array.
+    Empty,
+    /// Only `null` was seen.
+    Null,
+    Bool,
+    Int,
+    /// An integer above `i64::MAX`.
+    UInt,
+    Float,
+    String,
+    Array(Box<Sample>),
+    Object(Vec<(String, Sample)>),
+    /// Values that do not agree on a shape.
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Sample {
+    shape: Shape,
+    /// Whether `null` was seen.
+    nullable: bool,
+    /// Whether the key was missing from some objects.
+    missing: bool,
+}
+
+impl Sample {
+    fn new(shape: Shape) -> Self {
+        Sample {
+            shape,
+            nullable: false,
+            missing: false,
+        }
+    }
+
+    fn from_value(value: &Value) -> Self {
+        match value {
+            Value::Null => Sample {
+                nullable: true,
+                ..Sample::new(Shape::Null)
+            },
+            Value::Bool(_) => Sample::new(Shape::Bool),
+            Value::Number(v) if v.is_i64() => Sample::new(Shape::Int),
+            Value::Number(v) if v.is_u64() => Sample::new(Shape::UInt),
+            Value::Number(_) => Sample::new(Shape::Float),
+            Value::String(_) => Sample::new(Shape::String),
+            Value::Array(items) => Sample::new(Shape::Array(Box::new(
+                items
+                    .iter()
+                    .map(Sample::from_value)
+                    .fold(Sample::new(Shape::Empty), Sample::merge),
+            ))),
+            Value::Object(map) => Sample::new(Shape::Object(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), Sample::from_value(value)))
+                    .collect(),
+            )),
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        let shape = match (self.shape, other.shape) {
+            (Shape::Empty, v) | (v, Shape::Empty) => v,
+            (Shape::Null, v) | (v, Shape::Null) => v,
+            (Shape::Int, Shape::UInt) | (Shape::UInt, Shape::Int) => Shape::Float,
+            (Shape::Int | Shape::UInt, Shape::Float) | (Shape::Float, Shape::Int | Shape::UInt) => {
+                Shape::Float
+            }
+            (Shape::Array(a), Shape::Array(b)) => Shape::Array(Box::new(a.merge(*b))),
+            (Shape::Object(a), Shape::Object(b)) => Shape::Object(merge_fields(a, b)),
+            (a, b) if a == b => a,
+            _ => Shape::Any,
+        };
+
+        Sample {
+            shape,
+            nullable: self.nullable || other.nullable,
+            missing: self.missing || other.missing,
+        }
+    }
+}
+
+/// Merge the fields of two objects, marking the keys that only one of them
+/// has as missing.
+fn merge_fields(a: Vec<(String, Sample)>, mut b: Vec<(String, Sample)>) -> Vec<(String, Sample)> {
+    let mut ret = vec![];
+    for (key, sample) in a {
+        match b.iter().position(|(other, _)| other == &key) {
+            Some(index) => {
+                let (_, other) = b.remove(index);
+                ret.push((key, sample.merge(other)));
+            }
+            None => ret.push((
+                key,
+                Sample {
+                    missing: true,
+                    ..sample
+                },
+            )),
+        }
+    }
+    for (key, sample) in b {
+        ret.push((
+            key,
+            Sample {
+                missing: true,
+                ..sample
+            },
+        ));
+    }
+    ret
+}
+
+/// Write the fields of a struct inferred from a sample JSON object in the DSL.
+///
+/// Keys are turned into snake_case field names. When they differ, the original
+/// key is kept with `#[serde(rename = "...")]`, if `with_serde` is set.
+pub(crate) fn infer_struct_items(
+    root: &Ident,
+    value: &Value,
+    with_serde: bool,
+) -> Result<TokenStream, String> {
+    let Shape::Object(fields) = Sample::from_value(value).shape else {
+        return Err("the sample must be a JSON object".to_string());
+    };
+
+    let mut writer = Writer {
+        type_names: HashSet::from([root.to_string()]),
+        with_serde,
+    };
+    Ok(writer.fields(&root.to_string(), &fields))
+}
+
+struct Writer {
+    type_names: HashSet<String>,
+    with_serde: bool,
+}
+
+impl Writer {
+    fn fields(&mut self, parent: &str, fields: &[(String, Sample)]) -> TokenStream {
+        let mut field_names = HashSet::new();
+        let fields = fields
+            .iter()
+            .map(|(key, sample)| {
+                let name = unique_name(&key_field_name(key), &mut field_names);
+                let ident = key_field_ident(&name);
+                let rename = if self.with_serde && &name != key {
+                    quote! { #[serde(rename = #key)] }
+                } else {
+                    quote! {}
+                };
+                let optional = if sample.nullable || sample.missing {
+                    quote! { ? }
+                } else {
+                    quote! {}
+                };
+
+                let ty = match &sample.shape {
+                    Shape::Object(fields) => {
+                        let type_name = self.type_name(&key_type_name(key), parent);
+                        let fields = self.fields(&type_name, fields);
+                        let type_name = Ident::new(&type_name, Span::call_site());
+                        quote! { #type_name { #fields } }
+                    }
+                    Shape::Array(item) => match &item.shape {
+                        // Inline types cannot be wrapped in `Option` inside an
+                        // array, so arrays of objects holding `null` fall back.
+                        Shape::Object(_) if item.nullable => {
+                            quote! { Vec<Option<::yuuka::serde_json::Value>> }
+                        }
+                        Shape::Object(fields) => {
+                            let type_name = self.type_name(&key_type_name(&singular(key)), parent);
+                            let fields = self.fields(&type_name, fields);
+                            let type_name = Ident::new(&type_name, Span::call_site());
+                            quote! { [#type_name { #fields }] }
+                        }
+                        _ => static_type(&sample.shape),
+                    },
+                    shape => static_type(shape),
+                };
+
+                quote! {
+                    #rename
+                    #ident #optional: #ty,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        quote! { #(#fields)* }
+    }
+
+    /// A type name that is not taken yet, prefixed with the name of its
+    /// parent if needed.
+    fn type_name(&mut self, name: &str, parent: &str) -> String {
+        let name = if self.type_names.contains(name) {
+            format!("{}{}", parent, name)
+        } else {
+            name.to_string()
+        };
+        unique_name(&name, &mut self.type_names)
+    }
+}
+
+fn static_type(shape: &Shape) -> TokenStream {
+    match shape {
+        Shape::Bool => quote! { bool },
+        Shape::Int => quote! { i64 },
+        Shape::UInt => quote! { u64 },
+        Shape::Float => quote! { f64 },
+        Shape::String => quote! { String },
+        Shape::Array(item) => {
+            let ty = static_type(&item.shape);
+            if item.nullable {
+                quote! { Vec<Option<#ty>> }
+            } else {
+                quote! { Vec<#ty> }
+            }
+        }
+        // Nested inline types are only possible directly in a field.
+        Shape::Empty | Shape::Null | Shape::Object(_) | Shape::Any => {
+            quote! { ::yuuka::serde_json::Value }
+        }
+    }
+}
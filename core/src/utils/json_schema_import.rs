@@ -0,0 +1,708 @@
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::quote;
+use serde_json::{Map, Value};
+use syn::Ident;
+
+use super::{key_field_ident, key_field_name, key_type_name, singular, unique_name};
+
+/// What a JSON Schema turns into.
+enum Kind {
+    /// `$ref` to one of the `$defs`, by pointer.
+    Ref(String),
+    /// `enum` or `const` of strings.
+    StringEnum(Vec<String>),
+    /// `oneOf` or `anyOf`.
+    Union(Vec<Value>),
+    Object(Map<String, Value>, Vec<String>),
+    /// An object with `additionalProperties` but no `properties`.
+    Map(Value),
+    Array(Value),
+    Scalar(Scalar),
+    Any,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Scalar {
+    String,
+    Int,
+    UInt,
+    Float,
+    Bool,
+    Null,
+}
+
+impl Scalar {
+    fn to_type(self) -> TokenStream {
+        match self {
+            Scalar::String => quote! { String },
+            Scalar::Int => quote! { i64 },
+            Scalar::UInt => quote! { u64 },
+            Scalar::Float => quote! { f64 },
+            Scalar::Bool => quote! { bool },
+            Scalar::Null => quote! { () },
+        }
+    }
+
+    /// The DSL default value for a JSON `default`, if it fits the type.
+    fn literal(self, value: &Value) -> Option<TokenStream> {
+        Some(match (self, value) {
+            (Scalar::String, Value::String(v)) => quote! { #v.to_string() },
+            (Scalar::Int, Value::Number(v)) => {
+                let v = Literal::i64_unsuffixed(v.as_i64()?);
+                quote! { #v }
+            }
+            (Scalar::UInt, Value::Number(v)) => {
+                let v = Literal::u64_unsuffixed(v.as_u64()?);
+                quote! { #v }
+            }
+            (Scalar::Float, Value::Number(v)) => {
+                let v = Literal::f64_unsuffixed(v.as_f64()?);
+                quote! { #v }
+            }
+            (Scalar::Bool, Value::Bool(v)) => quote! { #v },
+            _ => return None,
+        })
+    }
+}
+
+fn kind(schema: &Value) -> Kind {
+    if let Some(Value::String(pointer)) = schema.get("$ref") {
+        return Kind::Ref(pointer.clone());
+    }
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        return match values.iter().map(Value::as_str).collect::<Option<Vec<_>>>() {
+            Some(values) if !values.is_empty() => {
+                Kind::StringEnum(values.into_iter().map(str::to_string).collect())
+            }
+            _ => Kind::Any,
+        };
+    }
+    if let Some(value) = schema.get("const") {
+        return match value.as_str() {
+            Some(value) => Kind::StringEnum(vec![value.to_string()]),
+            None => Kind::Any,
+        };
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(Value::Array(alternatives)) = schema.get(key) {
+            return Kind::Union(alternatives.clone());
+        }
+    }
+    if let Some(Value::Array(list)) = schema.get("allOf") {
+        if let [single] = list.as_slice() {
+            return kind(single);
+        }
+        return Kind::Any;
+    }
+
+    let ty = schema.get("type").and_then(Value::as_str);
+    if let Some(Value::Object(properties)) = schema.get("properties") {
+        if ty.map_or(true, |ty| ty == "object") {
+            let required = match schema.get("required") {
+                Some(Value::Array(list)) => list
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect(),
+                _ => vec![],
+            };
+            return Kind::Object(properties.clone(), required);
+        }
+    }
+    match ty {
+        Some("object") => match schema.get("additionalProperties") {
+            Some(value @ Value::Object(_)) => Kind::Map(value.clone()),
+            _ => Kind::Any,
+        },
+        Some("array") => Kind::Array(schema.get("items").cloned().unwrap_or(Value::Bool(true))),
+        Some("string") => Kind::Scalar(Scalar::String),
+        Some("integer") => {
+            let minimum = schema
+                .get("minimum")
+                .or_else(|| schema.get("exclusiveMinimum"))
+                .and_then(Value::as_f64);
+            if minimum.is_some_and(|v| v >= 0.) {
+                Kind::Scalar(Scalar::UInt)
+            } else {
+                Kind::Scalar(Scalar::Int)
+            }
+        }
+        Some("number") => Kind::Scalar(Scalar::Float),
+        Some("boolean") => Kind::Scalar(Scalar::Bool),
+        Some("null") => Kind::Scalar(Scalar::Null),
+        _ => Kind::Any,
+    }
+}
+
+/// Take `null` out of a schema, returning whether it was there. It may be a
+/// `type` of its own, or an alternative of `oneOf` or `anyOf`.
+fn strip_null(schema: &Value) -> (Value, bool) {
+    let mut schema = schema.clone();
+    let mut nullable = false;
+
+    if let Some(Value::Array(types)) = schema.get("type") {
+        let rest = types
+            .iter()
+            .filter(|ty| ty.as_str() != Some("null"))
+            .cloned()
+            .collect::<Vec<_>>();
+        if rest.len() < types.len() {
+            nullable = true;
+            schema["type"] = match rest.as_slice() {
+                [single] => single.clone(),
+                _ => Value::Array(rest),
+            };
+        }
+    }
+    for key in ["oneOf", "anyOf"] {
+        let Some(Value::Array(alternatives)) = schema.get(key) else {
+            continue;
+        };
+        let is_null = |alternative: &Value| {
+            alternative.get("type").and_then(Value::as_str) == Some("null")
+                || alternative.get("const") == Some(&Value::Null)
+        };
+        let rest = alternatives
+            .iter()
+            .filter(|alternative| !is_null(alternative))
+            .cloned()
+            .collect::<Vec<_>>();
+        if rest.len() == alternatives.len() {
+            continue;
+        }
+        nullable = true;
+        if let [single] = rest.as_slice() {
+            // Keep the description and default of the outer schema.
+            let Value::Object(mut outer) = schema.clone() else {
+                unreachable!()
+            };
+            outer.remove(key);
+            if let Value::Object(inner) = single {
+                for (k, v) in inner {
+                    outer.entry(k.clone()).or_insert(v.clone());
+                }
+            }
+            schema = Value::Object(outer);
+        } else {
+            schema[key] = Value::Array(rest);
+        }
+    }
+
+    (schema, nullable)
+}
+
+fn docs(schema: &Value) -> TokenStream {
+    let lines = schema
+        .get("description")
+        .and_then(Value::as_str)
+        .map(|text| {
+            text.lines()
+                .map(|line| format!(" {}", line))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    quote! { #(#[doc = #lines])* }
+}
+
+/// A generated type written in the DSL, with what the field holding it needs.
+struct TypeDef {
+    tokens: TokenStream,
+    /// The default variant of an enum, like `= Direct`.
+    default: TokenStream,
+    /// Attributes for the type itself, like `serde(untagged)`.
+    attrs: Vec<TokenStream>,
+}
+
+/// Write the fields of a struct described by a JSON Schema object in the DSL.
+///
+/// Named types come from `$defs` (or `definitions`) and are defined where
+/// they are first used, then referred to by name. A type that refers back to
+/// itself is boxed. Keys are turned into snake_case field names. When they
+/// differ, the original key is kept with `#[serde(rename = "...")]`, if
+/// `with_serde` is set.
+pub(crate) fn import_json_schema(
+    root: &Ident,
+    schema: &Value,
+    with_serde: bool,
+) -> Result<TokenStream, String> {
+    let mut importer = Importer {
+        defs: HashMap::new(),
+        defined: HashSet::new(),
+        in_progress: vec![],
+        type_names: HashSet::from([root.to_string()]),
+        with_serde,
+    };
+    for key in ["$defs", "definitions"] {
+        if let Some(Value::Object(defs)) = schema.get(key) {
+            for (name, def) in defs {
+                let type_name = unique_name(&key_type_name(name), &mut importer.type_names);
+                importer
+                    .defs
+                    .insert(format!("#/{}/{}", key, name), (type_name, def.clone()));
+            }
+        }
+    }
+
+    let schema = match kind(schema) {
+        Kind::Ref(pointer) => importer.def(&pointer)?.1,
+        _ => schema.clone(),
+    };
+    match kind(&schema) {
+        Kind::Object(properties, required) => {
+            importer.fields(&root.to_string(), &properties, &required)
+        }
+        _ => Err("the root of the schema must be an object with `properties`".to_string()),
+    }
+}
+
+struct Importer {
+    /// The type name and schema of every definition, by `$ref` pointer.
+    defs: HashMap<String, (String, Value)>,
+    /// The definitions that have been written out already.
+    defined: HashSet<String>,
+    /// The definitions being written out, to box references back to them.
+    in_progress: Vec<String>,
+    type_names: HashSet<String>,
+    with_serde: bool,
+}
+
+impl Importer {
+    fn def(&self, pointer: &str) -> Result<(String, Value), String> {
+        self.defs
+            .get(pointer)
+            .cloned()
+            .ok_or_else(|| format!("unsupported `$ref` to `{}`", pointer))
+    }
+
+    /// A type name that is not taken yet, from the `title` of the schema or
+    /// else from the key, prefixed with the name of its parent if needed.
+    fn type_name(&mut self, schema: &Value, key: &str, parent: &str) -> String {
+        let name = key_type_name(schema.get("title").and_then(Value::as_str).unwrap_or(key));
+        let name = if self.type_names.contains(&name) {
+            format!("{}{}", parent, name)
+        } else {
+            name
+        };
+        unique_name(&name, &mut self.type_names)
+    }
+
+    fn rename(&self, name: &str, key: &str) -> TokenStream {
+        if self.with_serde && name != key {
+            quote! { #[serde(rename = #key)] }
+        } else {
+            quote! {}
+        }
+    }
+
+    fn fields(
+        &mut self,
+        parent: &str,
+        properties: &Map<String, Value>,
+        required: &[String],
+    ) -> Result<TokenStream, String> {
+        let mut field_names = HashSet::new();
+        let mut fields = vec![];
+        for (key, schema) in properties {
+            let name = unique_name(&key_field_name(key), &mut field_names);
+            let ident = key_field_ident(&name);
+            let rename = self.rename(&name, key);
+            let docs = docs(schema);
+
+            let (schema, nullable) = strip_null(schema);
+            let optional = nullable || !required.contains(key);
+            let question = if optional {
+                quote! { ? }
+            } else {
+                quote! {}
+            };
+
+            let (ty, default, attrs) = self.field_type(&schema, key, parent, optional)?;
+            let attrs = if attrs.is_empty() {
+                quote! {}
+            } else {
+                quote! { #[yuuka(attr(#(#attrs),*))] }
+            };
+
+            fields.push(quote! {
+                #docs
+                #rename
+                #attrs
+                #ident #question: #ty #default,
+            });
+        }
+
+        Ok(quote! { #(#fields)* })
+    }
+
+    /// The type of a field, its DSL default and the attributes of its inline
+    /// type.
+    fn field_type(
+        &mut self,
+        schema: &Value,
+        key: &str,
+        parent: &str,
+        optional: bool,
+    ) -> Result<(TokenStream, TokenStream, Vec<TokenStream>), String> {
+        let default = |tokens: TokenStream| {
+            if optional {
+                quote! { = Some(#tokens) }
+            } else {
+                quote! { = #tokens }
+            }
+        };
+
+        Ok(match kind(schema) {
+            Kind::Ref(pointer) => match self.reference(&pointer)? {
+                Ok(def) => (def.tokens, def.default, def.attrs),
+                Err(ty) => (ty, quote! {}, vec![]),
+            },
+            Kind::Array(items) => {
+                let (items, nullable) = strip_null(&items);
+                let def = match kind(&items) {
+                    Kind::Ref(pointer) => self.reference(&pointer)?,
+                    Kind::Object(..) | Kind::StringEnum(_) | Kind::Union(_) if !nullable => {
+                        let name = self.type_name(&items, &singular(key), parent);
+                        Ok(self.type_def(&name, &items)?)
+                    }
+                    _ => Err(self.static_type(&items)?),
+                };
+                match def {
+                    // Default values of inline enums in arrays are not written.
+                    Ok(def) => {
+                        let tokens = def.tokens;
+                        (quote! { [#tokens] }, quote! {}, def.attrs)
+                    }
+                    Err(ty) => {
+                        let ty = if nullable {
+                            quote! { Option<#ty> }
+                        } else {
+                            ty
+                        };
+                        let default = match schema.get("default").and_then(|v| {
+                            let scalar = match kind(&items) {
+                                Kind::Scalar(scalar) => scalar,
+                                _ => return None,
+                            };
+                            v.as_array()?
+                                .iter()
+                                .map(|v| scalar.literal(v))
+                                .collect::<Option<Vec<_>>>()
+                        }) {
+                            Some(list) if !nullable => default(quote! { vec![#(#list),*] }),
+                            _ => quote! {},
+                        };
+                        (quote! { Vec<#ty> }, default, vec![])
+                    }
+                }
+            }
+            Kind::Object(..) | Kind::StringEnum(_) | Kind::Union(_) => {
+                let name = self.type_name(schema, key, parent);
+                let def = self.type_def(&name, schema)?;
+                // The default variant of an optional enum is not needed.
+                let default = if optional {
+                    quote! {}
+                } else {
+                    def.default
+                };
+                (def.tokens, default, def.attrs)
+            }
+            Kind::Scalar(scalar) => {
+                let default = schema
+                    .get("default")
+                    .and_then(|v| scalar.literal(v))
+                    .map(default)
+                    .unwrap_or_default();
+                (scalar.to_type(), default, vec![])
+            }
+            Kind::Map(_) | Kind::Any => (self.static_type(schema)?, quote! {}, vec![]),
+        })
+    }
+
+    /// Write out a definition where it is first used, or refer to it by name
+    /// afterwards.
+    fn reference(&mut self, pointer: &str) -> Result<Result<TypeDef, TokenStream>, String> {
+        let (name, schema) = self.def(pointer)?;
+        let ident = Ident::new(&name, Span::call_site());
+        if self.in_progress.iter().any(|v| v == pointer) {
+            return Ok(Err(quote! { Box<#ident> }));
+        }
+        if self.defined.contains(pointer) {
+            return Ok(Err(quote! { #ident }));
+        }
+        if !matches!(
+            kind(&schema),
+            Kind::Object(..) | Kind::StringEnum(_) | Kind::Union(_)
+        ) {
+            // Definitions of plain types are written out in place.
+            return Ok(Err(self.static_type(&schema)?));
+        }
+
+        self.defined.insert(pointer.to_string());
+        self.in_progress.push(pointer.to_string());
+        let def = self.type_def(&name, &schema);
+        self.in_progress.pop();
+        Ok(Ok(def?))
+    }
+
+    /// A type that can only be written out as a Rust type, not as an inline
+    /// definition, such as the items of a `Vec` inside another type.
+    fn static_type(&mut self, schema: &Value) -> Result<TokenStream, String> {
+        let (schema, nullable) = strip_null(schema);
+        let ty = match kind(&schema) {
+            Kind::Ref(pointer) => match self.reference(&pointer)? {
+                Err(ty) => ty,
+                // Only reached for definitions used for the first time in a
+                // position that cannot hold an inline definition.
+                Ok(_) => {
+                    self.defined.remove(&pointer);
+                    quote! { ::yuuka::serde_json::Value }
+                }
+            },
+            Kind::Scalar(scalar) => scalar.to_type(),
+            Kind::Array(items) => {
+                let items = self.static_type(&items)?;
+                quote! { Vec<#items> }
+            }
+            Kind::Map(values) => {
+                let values = self.static_type(&values)?;
+                quote! { ::std::collections::HashMap<String, #values> }
+            }
+            _ => quote! { ::yuuka::serde_json::Value },
+        };
+        Ok(if nullable {
+            quote! { Option<#ty> }
+        } else {
+            ty
+        })
+    }
+
+    fn type_def(&mut self, name: &str, schema: &Value) -> Result<TypeDef, String> {
+        let ident = Ident::new(name, Span::call_site());
+        match kind(schema) {
+            Kind::Object(properties, required) => {
+                let fields = self.fields(name, &properties, &required)?;
+                Ok(TypeDef {
+                    tokens: quote! { #ident { #fields } },
+                    default: quote! {},
+                    attrs: vec![],
+                })
+            }
+            Kind::StringEnum(values) => {
+                let mut variant_names = HashSet::new();
+                let variants = values
+                    .iter()
+                    .map(|value| {
+                        let variant = unique_name(&key_type_name(value), &mut variant_names);
+                        (value, variant)
+                    })
+                    .collect::<Vec<_>>();
+                let default = schema
+                    .get("default")
+                    .and_then(Value::as_str)
+                    .and_then(|default| variants.iter().find(|(value, _)| *value == default))
+                    .or(variants.first())
+                    .map(|(_, variant)| Ident::new(variant, Span::call_site()));
+                let variants = variants
+                    .iter()
+                    .map(|(value, variant)| {
+                        let rename = self.rename(variant, value);
+                        let variant = Ident::new(variant, Span::call_site());
+                        quote! { #rename #variant, }
+                    })
+                    .collect::<Vec<_>>();
+
+                Ok(TypeDef {
+                    tokens: quote! { enum #ident { #(#variants)* } },
+                    default: quote! { = #default },
+                    attrs: vec![],
+                })
+            }
+            Kind::Union(alternatives) => self.union(&ident, &alternatives),
+            _ => unreachable!("only objects and enums have inline definitions"),
+        }
+    }
+
+    /// An enum from `oneOf` or `anyOf`, externally tagged if every
+    /// alternative looks like serde's default representation, internally
+    /// tagged if they share a `const` property, and untagged otherwise.
+    fn union(&mut self, ident: &Ident, alternatives: &[Value]) -> Result<TypeDef, String> {
+        let name = ident.to_string();
+        let alternatives = alternatives
+            .iter()
+            .map(|alternative| match kind(alternative) {
+                Kind::Ref(pointer) => Ok(self.def(&pointer)?.1),
+                _ => Ok(alternative.clone()),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mut variant_names = HashSet::new();
+        let mut variants = vec![];
+        let mut attrs = vec![];
+
+        if let Some(tagged) = alternatives
+            .iter()
+            .map(external_variant)
+            .collect::<Option<Vec<_>>>()
+        {
+            for (key, content) in tagged {
+                let variant = unique_name(&key_type_name(&key), &mut variant_names);
+                let rename = self.rename(&variant, &key);
+                variants.push(self.variant(&name, &variant, rename, content.as_ref())?);
+            }
+        } else if let Some((tag, tagged)) =
+            internal_variants(&alternatives).filter(|_| self.with_serde)
+        {
+            attrs.push(quote! { serde(tag = #tag) });
+            for (key, content) in tagged {
+                let variant = unique_name(&key_type_name(&key), &mut variant_names);
+                let rename = self.rename(&variant, &key);
+                variants.push(self.variant(&name, &variant, rename, Some(&content))?);
+            }
+        } else {
+            if self.with_serde {
+                attrs.push(quote! { serde(untagged) });
+            }
+            for alternative in &alternatives {
+                let label = alternative
+                    .get("title")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| match kind(alternative) {
+                        Kind::Scalar(Scalar::String) | Kind::StringEnum(_) => "String".into(),
+                        Kind::Scalar(Scalar::Int | Scalar::UInt) => "Integer".into(),
+                        Kind::Scalar(Scalar::Float) => "Number".into(),
+                        Kind::Scalar(Scalar::Bool) => "Boolean".into(),
+                        Kind::Scalar(Scalar::Null) => "Null".into(),
+                        Kind::Array(_) => "Array".into(),
+                        _ => "Object".into(),
+                    });
+                let variant = unique_name(&key_type_name(&label), &mut variant_names);
+                variants.push(self.variant(&name, &variant, quote! {}, Some(alternative))?);
+            }
+        }
+
+        let tokens = variants
+            .iter()
+            .map(|(tokens, _)| tokens.clone())
+            .collect::<Vec<_>>();
+        // Prefer a unit variant, whose default needs nothing else.
+        let default = variants
+            .iter()
+            .find(|(_, value)| value.clone().into_iter().count() == 1)
+            .or(variants.first())
+            .map(|(_, value)| value.clone());
+        Ok(TypeDef {
+            tokens: quote! { enum #ident { #(#tokens)* } },
+            default: default.map(|v| quote! { = #v }).unwrap_or_default(),
+            attrs,
+        })
+    }
+
+    /// A variant and the expression of its default value.
+    fn variant(
+        &mut self,
+        parent: &str,
+        variant: &str,
+        rename: TokenStream,
+        content: Option<&Value>,
+    ) -> Result<(TokenStream, TokenStream), String> {
+        let ident = Ident::new(variant, Span::call_site());
+        let Some(content) = content else {
+            return Ok((quote! { #rename #ident, }, quote! { #ident }));
+        };
+
+        match kind(content) {
+            Kind::Object(properties, required) => {
+                let fields = self.fields(parent, &properties, &required)?;
+                let defaults = properties
+                    .keys()
+                    .scan(HashSet::new(), |names, key| {
+                        let name = unique_name(&key_field_name(key), names);
+                        Some(key_field_ident(&name))
+                    })
+                    .map(|field| quote! { #field: Default::default() })
+                    .collect::<Vec<_>>();
+                Ok((
+                    quote! { #rename #ident { #fields }, },
+                    quote! { #ident { #(#defaults),* } },
+                ))
+            }
+            _ => {
+                let ty = match kind(content) {
+                    Kind::StringEnum(_) | Kind::Union(_) => {
+                        let name = self.type_name(content, variant, parent);
+                        let def = self.type_def(&name, content)?;
+                        // Inline enums in tuples cannot take attributes.
+                        if def.attrs.is_empty() {
+                            def.tokens
+                        } else {
+                            quote! { ::yuuka::serde_json::Value }
+                        }
+                    }
+                    Kind::Ref(pointer) => match self.reference(&pointer)? {
+                        Ok(def) if def.attrs.is_empty() => def.tokens,
+                        Ok(_) => quote! { ::yuuka::serde_json::Value },
+                        Err(ty) => ty,
+                    },
+                    _ => self.static_type(content)?,
+                };
+                Ok((
+                    quote! { #rename #ident(#ty), },
+                    quote! { #ident(Default::default()) },
+                ))
+            }
+        }
+    }
+}
+
+/// `{ "const": "Name" }` for a unit variant, or an object with a single
+/// required property for a variant with content, as serde writes them by
+/// default.
+fn external_variant(alternative: &Value) -> Option<(String, Option<Value>)> {
+    match kind(alternative) {
+        Kind::StringEnum(values) if values.len() == 1 => Some((values[0].clone(), None)),
+        Kind::Object(properties, required) if properties.len() == 1 && required.len() == 1 => {
+            let (key, content) = properties.into_iter().next()?;
+            (key == required[0]).then_some((key, Some(content)))
+        }
+        _ => None,
+    }
+}
+
+/// The tag of objects that all share a property with a `const` string value,
+/// with each variant name and the rest of its object.
+#[allow(clippy::type_complexity)]
+fn internal_variants(alternatives: &[Value]) -> Option<(String, Vec<(String, Value)>)> {
+    let first = alternatives.first()?.get("properties")?.as_object()?;
+    let tag = first.iter().find_map(|(key, value)| {
+        value.get("const")?.as_str()?;
+        alternatives
+            .iter()
+            .all(|alternative| {
+                alternative
+                    .pointer(&format!("/properties/{}/const", key))
+                    .and_then(Value::as_str)
+                    .is_some()
+            })
+            .then(|| key.clone())
+    })?;
+
+    let variants = alternatives
+        .iter()
+        .map(|alternative| {
+            let name = alternative
+                .pointer(&format!("/properties/{}/const", tag))?
+                .as_str()?
+                .to_string();
+            let mut content = alternative.clone();
+            content.get_mut("properties")?.as_object_mut()?.remove(&tag);
+            if let Some(Value::Array(required)) = content.get_mut("required") {
+                required.retain(|key| key.as_str() != Some(&tag));
+            }
+            Some((name, content))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some((tag, variants))
+}
@@ -0,0 +1,21 @@
+mod errors;
+mod flatten;
+mod glob;
+mod json_infer;
+mod json_schema_import;
+mod naming;
+mod pretty;
+mod serde_info;
+mod validate;
+
+pub(crate) use errors::combine_errors;
+pub(crate) use flatten::{flatten, FlattenContext};
+pub(crate) use glob::glob_match;
+pub(crate) use json_infer::infer_struct_items;
+pub(crate) use json_schema_import::import_json_schema;
+pub(crate) use naming::{
+    check_unique_names, key_field_ident, key_field_name, key_type_name, singular, unique_name,
+};
+pub(crate) use pretty::{pretty, pretty_inline};
+pub(crate) use serde_info::{literal_json, RenameRule, SerdeAttrs};
+pub(crate) use validate::validate;
@@ -0,0 +1,182 @@
+use proc_macro2::Span;
+use quote::format_ident;
+use std::collections::{HashMap, HashSet};
+use syn::Ident;
+
+use super::combine_errors;
+use crate::tools::{DeriveAnonymousNaming, EnumsFlatten, StructsFlatten};
+
+/// Build the name of an anonymous type.
+///
+/// `path` holds the field and variant names leading from the parent type,
+/// whose final name is `parent_name`, to the anonymous type.
+pub(crate) fn anonymous_name(
+    naming: DeriveAnonymousNaming,
+    root_name: &str,
+    unique_id: usize,
+    parent_name: &str,
+    path: &[String],
+) -> String {
+    match naming {
+        DeriveAnonymousNaming::Counter => format!("_{}_{}_anonymous", root_name, unique_id),
+        DeriveAnonymousNaming::Path => [parent_name.to_string()]
+            .into_iter()
+            .chain(path.iter().map(|segment| to_upper_camel(segment)))
+            .collect::<Vec<_>>()
+            .join("_"),
+        DeriveAnonymousNaming::PathCamel => [parent_name.to_string()]
+            .into_iter()
+            .chain(path.iter().map(|segment| to_upper_camel(segment)))
+            .collect::<Vec<_>>()
+            .concat(),
+    }
+}
+
+/// Convert a field or variant name such as `nick_name` into `NickName`.
+pub(crate) fn to_upper_camel(segment: &str) -> String {
+    segment
+        .trim_start_matches("r#")
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Make sure no two generated types ended up with the same name.
+pub(crate) fn check_unique_names(
+    structs: &StructsFlatten,
+    enums: &EnumsFlatten,
+) -> syn::Result<()> {
+    let mut names: HashMap<String, &Ident> = HashMap::new();
+    let mut errors = vec![];
+    for ident in structs
+        .iter()
+        .map(|(ident, _, _)| ident)
+        .chain(enums.iter().map(|(ident, _, _, _)| ident))
+    {
+        if let Some(first) = names.get(&ident.to_string()) {
+            errors.push(syn::Error::new(
+                ident.span(),
+                format!(
+                    "the generated type name `{}` is used more than once; \
+                     rename one of the types or choose another anonymous naming strategy",
+                    ident
+                ),
+            ));
+            errors.push(syn::Error::new(
+                first.span(),
+                format!("`{}` is first defined here", ident),
+            ));
+        } else {
+            names.insert(ident.to_string(), ident);
+        }
+    }
+
+    combine_errors(errors)
+}
+
+/// `name`, or `name_2`, `name_3` and so on if it is taken.
+pub(crate) fn unique_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let mut ret = name.to_string();
+    let mut index = 2;
+    while taken.contains(&ret) {
+        ret = format!("{}_{}", name, index);
+        index += 1;
+    }
+    taken.insert(ret.clone());
+    ret
+}
+
+/// The words of a key, split at case changes and at anything that is not a
+/// letter or a digit.
+fn words(key: &str) -> Vec<String> {
+    let chars = key.chars().collect::<Vec<_>>();
+    let mut words = vec![];
+    let mut word = String::new();
+    for (index, ch) in chars.iter().enumerate() {
+        if !ch.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        let is_word_start = ch.is_uppercase()
+            && index > 0
+            && (chars[index - 1].is_lowercase()
+                || chars[index - 1].is_ascii_digit()
+                || chars.get(index + 1).is_some_and(|ch| ch.is_lowercase()));
+        if is_word_start && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(*ch);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// A snake_case field name for a JSON key.
+pub(crate) fn key_field_name(key: &str) -> String {
+    let name = words(key)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    match name.chars().next() {
+        None => "field".to_string(),
+        Some(ch) if ch.is_ascii_digit() => format!("_{}", name),
+        Some(_) => name,
+    }
+}
+
+/// The identifier of a field name, raw if it is a keyword.
+pub(crate) fn key_field_ident(name: &str) -> Ident {
+    match name {
+        // These cannot be raw identifiers.
+        "self" | "super" | "crate" => format_ident!("{}_", name),
+        _ if syn::parse_str::<Ident>(name).is_ok() => Ident::new(name, Span::call_site()),
+        _ => Ident::new_raw(name, Span::call_site()),
+    }
+}
+
+/// A PascalCase type name for a JSON key.
+pub(crate) fn key_type_name(key: &str) -> String {
+    let name = words(key)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<String>();
+    match name.chars().next() {
+        None => "Item".to_string(),
+        Some(ch) if ch.is_ascii_digit() => format!("Item{}", name),
+        // Keywords like `Self`.
+        Some(_) if syn::parse_str::<Ident>(&name).is_err() => format!("{}Item", name),
+        Some(_) => name,
+    }
+}
+
+/// A rough singular of a plural key, to name the items of an array.
+pub(crate) fn singular(key: &str) -> String {
+    if let Some(stem) = key.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if key.len() > 1 && key.ends_with('s') && !key.ends_with("ss") {
+        key[..key.len() - 1].to_string()
+    } else {
+        format!("{}Item", key)
+    }
+}
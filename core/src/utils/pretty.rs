@@ -0,0 +1,374 @@
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+
+/// Rust source for some generated tokens, one item, field or statement per
+/// line and indented by braces.
+///
+/// This does not try to match rustfmt, only to be readable without it. Doc
+/// attributes are written back as `///` comments.
+pub(crate) fn pretty(tokens: TokenStream) -> String {
+    let mut printer = Printer {
+        text: String::new(),
+        indent: 0,
+        prev: Prev::LineStart,
+        joint: false,
+        inline: false,
+    };
+    printer.stream(tokens, Delimiter::Brace);
+    let mut text = printer.text.trim_end().to_string();
+    text.push('\n');
+    text
+}
+
+/// Rust source for a type, an expression or the inside of an attribute, on
+/// one line. The schemas, the IR and the formatter all write such text with
+/// it, so they agree on the spacing.
+pub(crate) fn pretty_inline(tokens: impl ToTokens) -> String {
+    let mut printer = Printer {
+        text: String::new(),
+        indent: 0,
+        prev: Prev::LineStart,
+        joint: false,
+        inline: true,
+    };
+    printer.stream(tokens.into_token_stream(), Delimiter::Parenthesis);
+    printer.text.trim_end().to_string()
+}
+
+/// What was written last, to decide on the space before the next token.
+#[derive(Clone, PartialEq)]
+enum Prev {
+    LineStart,
+    Open,
+    Close,
+    /// The end of `$(...)` in a macro, where the repetition operator follows.
+    Repetition,
+    Word {
+        keyword: bool,
+    },
+    Literal,
+    /// An operator, with every character of it when it is made of several
+    /// joint punctuation marks, like `::` or `=>`.
+    Op {
+        text: String,
+        unary: bool,
+    },
+}
+
+struct Printer {
+    text: String,
+    indent: usize,
+    prev: Prev,
+    /// Whether the last punctuation mark is joint with the next one.
+    joint: bool,
+    /// Whether everything goes on one line, braces included.
+    inline: bool,
+}
+
+/// Whether a token starts an item, which gets a blank line before it.
+fn starts_item(token: Option<&TokenTree>) -> bool {
+    match token {
+        Some(TokenTree::Punct(punct)) => punct.as_char() == '#',
+        Some(TokenTree::Ident(ident)) => [
+            "pub",
+            "impl",
+            "fn",
+            "struct",
+            "enum",
+            "mod",
+            "macro_rules",
+            "use",
+            "const",
+            "static",
+            "type",
+            "trait",
+        ]
+        .iter()
+        .any(|keyword| ident == keyword),
+        _ => false,
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "fn",
+    "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "type", "unsafe", "use", "where", "while",
+];
+
+impl Printer {
+    fn newline(&mut self) {
+        if self.inline || self.prev == Prev::LineStart {
+            return;
+        }
+        let len = self.text.trim_end_matches(' ').len();
+        self.text.truncate(len);
+        self.text.push('\n');
+        self.prev = Prev::LineStart;
+    }
+
+    fn write(&mut self, text: &str, space: bool) {
+        if self.prev == Prev::LineStart {
+            self.text.push_str(&"    ".repeat(self.indent));
+        } else if space {
+            self.text.push(' ');
+        }
+        self.text.push_str(text);
+    }
+
+    /// Whether a binary operator, rather than a unary one, would follow.
+    fn after_operand(&self) -> bool {
+        match &self.prev {
+            Prev::Close | Prev::Repetition | Prev::Literal => true,
+            Prev::Word { keyword } => !keyword,
+            Prev::Op { text, .. } => text == ">" || text == "?",
+            Prev::LineStart | Prev::Open => false,
+        }
+    }
+
+    fn space_before_word(&self) -> bool {
+        match &self.prev {
+            Prev::LineStart | Prev::Open => false,
+            Prev::Op { text, unary } => {
+                !(*unary || matches!(text.as_str(), "::" | "." | "!" | "#" | "$" | "<" | "'"))
+            }
+            _ => true,
+        }
+    }
+
+    fn stream(&mut self, tokens: TokenStream, delimiter: Delimiter) {
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Group(group) => {
+                    self.group(group.delimiter(), group.stream());
+                    if group.delimiter() == Delimiter::Brace && delimiter == Delimiter::Brace {
+                        let attached = match tokens.peek() {
+                            Some(TokenTree::Punct(punct)) => {
+                                matches!(punct.as_char(), ',' | ';' | '.' | '?')
+                            }
+                            Some(TokenTree::Ident(ident)) => ident == "else",
+                            _ => false,
+                        };
+                        if !attached && !self.inline {
+                            self.newline();
+                            if starts_item(tokens.peek()) {
+                                self.text.push('\n');
+                            }
+                        }
+                    }
+                }
+                TokenTree::Ident(ident) => {
+                    let text = ident.to_string();
+                    let space = self.space_before_word() || self.text.ends_with("macro_rules!");
+                    self.write(&text, space);
+                    self.prev = Prev::Word {
+                        keyword: KEYWORDS.contains(&text.as_str()),
+                    };
+                }
+                TokenTree::Literal(literal) => {
+                    let space = self.space_before_word();
+                    self.write(&literal.to_string(), space);
+                    self.prev = Prev::Literal;
+                }
+                TokenTree::Punct(punct) => {
+                    let ch = punct.as_char();
+                    let is_attr = ch == '#'
+                        && matches!(
+                            tokens.peek(),
+                            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket
+                        );
+                    if is_attr && delimiter == Delimiter::Brace && !self.inline {
+                        let Some(TokenTree::Group(group)) = tokens.next() else {
+                            unreachable!()
+                        };
+                        self.attribute(group.stream());
+                        continue;
+                    }
+                    let repetition = self.prev == Prev::Repetition;
+                    self.punct(ch, punct.spacing());
+                    if !repetition
+                        && punct.spacing() == Spacing::Alone
+                        && delimiter == Delimiter::Brace
+                        && !self.inline
+                        && matches!(ch, ',' | ';')
+                    {
+                        let statement = self.text[self.text.rfind('\n').map_or(0, |i| i + 1)..]
+                            .trim_start()
+                            .to_string();
+                        self.newline();
+                        // After the `use` lines at the top of a module.
+                        let next_is_use = matches!(tokens.peek(), Some(TokenTree::Ident(ident)) if ident == "use");
+                        if statement.starts_with("use ")
+                            && !next_is_use
+                            && starts_item(tokens.peek())
+                        {
+                            self.text.push('\n');
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn attribute(&mut self, tokens: TokenStream) {
+        if let Ok(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            value:
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(text),
+                    ..
+                }),
+            ..
+        })) = syn::parse2::<syn::Meta>(tokens.clone())
+        {
+            if path.is_ident("doc") {
+                self.newline();
+                for line in text.value().lines() {
+                    self.write(&format!("///{}", line), false);
+                    self.prev = Prev::Close;
+                    self.newline();
+                }
+                return;
+            }
+        }
+
+        self.newline();
+        self.write("#", false);
+        self.prev = Prev::Op {
+            text: "#".to_string(),
+            unary: true,
+        };
+        self.group(Delimiter::Bracket, tokens);
+        self.newline();
+    }
+
+    fn group(&mut self, delimiter: Delimiter, tokens: TokenStream) {
+        match delimiter {
+            Delimiter::Brace => {
+                let space = !matches!(self.prev, Prev::LineStart | Prev::Open);
+                if tokens.is_empty() {
+                    self.write("{}", space);
+                } else if self.inline {
+                    self.write("{ ", space);
+                    self.prev = Prev::Open;
+                    self.stream(tokens, Delimiter::Brace);
+                    self.write("}", true);
+                } else {
+                    self.write("{", space);
+                    self.prev = Prev::Open;
+                    self.indent += 1;
+                    self.newline();
+                    self.stream(tokens, Delimiter::Brace);
+                    self.newline();
+                    self.indent -= 1;
+                    self.write("}", false);
+                }
+            }
+            Delimiter::Parenthesis | Delimiter::Bracket => {
+                let (open, close) = if delimiter == Delimiter::Parenthesis {
+                    ("(", ")")
+                } else {
+                    ("[", "]")
+                };
+                let space = match &self.prev {
+                    Prev::Word { keyword } => *keyword && !matches!(self.last_word(), "pub"),
+                    Prev::Op { text, unary } => {
+                        !*unary && !matches!(text.as_str(), "!" | "$" | "#" | "::" | ">" | "<")
+                    }
+                    Prev::Literal => true,
+                    Prev::LineStart | Prev::Open | Prev::Close | Prev::Repetition => false,
+                };
+                let repetition = matches!(&self.prev, Prev::Op { text, .. } if text == "$");
+                self.write(open, space);
+                self.prev = Prev::Open;
+                self.stream(tokens, delimiter);
+                self.write(close, false);
+                if repetition {
+                    self.prev = Prev::Repetition;
+                    return;
+                }
+            }
+            Delimiter::None => {
+                self.stream(tokens, delimiter);
+                return;
+            }
+        }
+        self.prev = Prev::Close;
+    }
+
+    fn last_word(&self) -> &str {
+        self.text
+            .rsplit(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+            .next()
+            .unwrap_or_default()
+    }
+
+    fn punct(&mut self, ch: char, spacing: Spacing) {
+        // The second and later marks of an operator like `::` or `=>`.
+        if let Prev::Op { text, unary } = &self.prev {
+            if self.joint {
+                let text = format!("{}{}", text, ch);
+                let unary = *unary;
+                self.text.push(ch);
+                self.joint = spacing == Spacing::Joint;
+                self.prev = Prev::Op { text, unary };
+                return;
+            }
+        }
+
+        // `$(...),*` and `$(...)+`, where a separator keeps the operator close.
+        if self.prev == Prev::Repetition {
+            self.write(&ch.to_string(), false);
+            self.joint = false;
+            self.prev = if matches!(ch, ',' | ';') {
+                Prev::Repetition
+            } else {
+                Prev::Close
+            };
+            return;
+        }
+        // `$name:ident` in a macro.
+        if ch == ':' && spacing == Spacing::Alone {
+            let word = self.last_word();
+            let fragment =
+                !word.is_empty() && self.text[..self.text.len() - word.len()].ends_with('$');
+            if fragment {
+                self.write(":", false);
+                self.joint = false;
+                self.prev = Prev::Op {
+                    text: ":".to_string(),
+                    unary: true,
+                };
+                return;
+            }
+        }
+
+        let unary = !self.after_operand();
+        let space = match ch {
+            // `0 .` rather than `0.`, which would read back as a float.
+            '.' if self.prev == Prev::Literal => {
+                let word = self.last_word();
+                !word.is_empty() && word.chars().all(|c| c.is_ascii_digit())
+            }
+            ',' | ';' | '.' | '?' => false,
+            ':' if spacing == Spacing::Alone => false,
+            ':' => match &self.prev {
+                Prev::Word { keyword } => *keyword,
+                Prev::Op { text, .. } => text != ">" && text != "<" && text != "&",
+                _ => false,
+            },
+            // The end of generic arguments, also when it is joint with a following `,`.
+            '>' => false,
+            '<' => !matches!(self.prev, Prev::Word { .. } | Prev::Op { .. }),
+            '!' if matches!(self.prev, Prev::Word { keyword: false }) => false,
+            '#' | '$' | '\'' | '!' | '&' | '*' | '-' if unary => self.space_before_word(),
+            _ => !matches!(self.prev, Prev::LineStart | Prev::Open),
+        };
+        self.write(&ch.to_string(), space);
+        self.joint = spacing == Spacing::Joint;
+        self.prev = Prev::Op {
+            text: ch.to_string(),
+            unary: unary && matches!(ch, '&' | '*' | '-' | '!'),
+        };
+    }
+}
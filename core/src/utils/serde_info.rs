@@ -0,0 +1,266 @@
+use proc_macro2::TokenStream;
+use serde_json::Value;
+use syn::{punctuated::Punctuated, Expr, ExprLit, Lit, Meta, Token, UnOp};
+
+/// The `rename_all` rules of serde.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(rule: &str) -> Option<Self> {
+        Some(match rule {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    /// Rename a `snake_case` field the way serde does.
+    pub(crate) fn apply_to_field(&self, field: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => field.to_string(),
+            Self::Upper | Self::ScreamingSnake => field.to_ascii_uppercase(),
+            Self::Pascal | Self::Camel => {
+                let mut ret = String::new();
+                let mut capitalize = *self == Self::Pascal;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        ret.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        ret.push(ch);
+                    }
+                }
+                ret
+            }
+            Self::Kebab => field.replace('_', "-"),
+            Self::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+
+    /// Rename a `PascalCase` variant the way serde does.
+    pub(crate) fn apply_to_variant(&self, variant: &str) -> String {
+        match self {
+            Self::Pascal => variant.to_string(),
+            Self::Lower => variant.to_ascii_lowercase(),
+            Self::Upper => variant.to_ascii_uppercase(),
+            Self::Camel => {
+                let mut chars = variant.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            }
+            Self::Snake | Self::ScreamingSnake | Self::Kebab | Self::ScreamingKebab => {
+                let mut snake = String::new();
+                for (index, ch) in variant.char_indices() {
+                    if index > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                match self {
+                    Self::Snake => snake,
+                    _ => self.apply_to_field(&snake),
+                }
+            }
+        }
+    }
+}
+
+/// The serde attributes of a struct, enum, variant or field that change how it
+/// is serialized. Only the options that affect the shape of the data are kept.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SerdeAttrs {
+    pub(crate) rename: Option<String>,
+    pub(crate) rename_all: Option<RenameRule>,
+    pub(crate) rename_all_fields: Option<RenameRule>,
+    pub(crate) tag: Option<String>,
+    pub(crate) content: Option<String>,
+    pub(crate) untagged: bool,
+    pub(crate) default: bool,
+    pub(crate) skip: bool,
+    pub(crate) deny_unknown_fields: bool,
+}
+
+impl SerdeAttrs {
+    /// Collect the `serde(...)` attributes among `attrs`, ignoring the others
+    /// and anything that cannot be read.
+    pub(crate) fn parse<'a>(attrs: impl IntoIterator<Item = &'a TokenStream>) -> Self {
+        let mut ret = Self::default();
+        for attr in attrs {
+            let Ok(Meta::List(list)) = syn::parse2::<Meta>(attr.clone()) else {
+                continue;
+            };
+            if !list.path.is_ident("serde") {
+                continue;
+            }
+            let Ok(items) = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            else {
+                continue;
+            };
+
+            for item in items {
+                ret.apply(&item);
+            }
+        }
+        ret
+    }
+
+    fn apply(&mut self, item: &Meta) {
+        let Some(key) = item.path().get_ident().map(|ident| ident.to_string()) else {
+            return;
+        };
+
+        match item {
+            Meta::Path(_) => match key.as_str() {
+                "untagged" => self.untagged = true,
+                "default" => self.default = true,
+                "skip" | "skip_deserializing" => self.skip = true,
+                "deny_unknown_fields" => self.deny_unknown_fields = true,
+                _ => {}
+            },
+            Meta::NameValue(pair) => match key.as_str() {
+                "default" => self.default = true,
+                "rename" => self.rename = lit_str(&pair.value),
+                "rename_all" => {
+                    self.rename_all = lit_str(&pair.value).and_then(|v| RenameRule::parse(&v))
+                }
+                "rename_all_fields" => {
+                    self.rename_all_fields =
+                        lit_str(&pair.value).and_then(|v| RenameRule::parse(&v))
+                }
+                "tag" => self.tag = lit_str(&pair.value),
+                "content" => self.content = lit_str(&pair.value),
+                _ => {}
+            },
+            // `rename(serialize = "...", deserialize = "...")`, where the name
+            // used when reading data is the one that matters for a schema.
+            Meta::List(list) => {
+                let Ok(items) =
+                    list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                else {
+                    return;
+                };
+                for item in items {
+                    let Meta::NameValue(pair) = item else {
+                        continue;
+                    };
+                    if !pair.path.is_ident("deserialize") {
+                        continue;
+                    }
+                    match key.as_str() {
+                        "rename" => self.rename = lit_str(&pair.value),
+                        "rename_all" => {
+                            self.rename_all =
+                                lit_str(&pair.value).and_then(|v| RenameRule::parse(&v))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn lit_str(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(v), ..
+        }) => Some(v.value()),
+        _ => None,
+    }
+}
+
+/// The JSON value of a DSL default written as a literal, like `8080`,
+/// `"yuuka".to_string()`, `Some(1.5)` or `vec![1, 2]`. Anything computed is
+/// left out.
+pub(crate) fn literal_json(expr: &Expr) -> Option<Value> {
+    match expr {
+        Expr::Lit(ExprLit { lit, .. }) => match lit {
+            Lit::Str(v) => Some(Value::String(v.value())),
+            Lit::Char(v) => Some(Value::String(v.value().to_string())),
+            Lit::Bool(v) => Some(Value::Bool(v.value)),
+            Lit::Int(v) => v
+                .base10_parse::<u64>()
+                .ok()
+                .map(Value::from)
+                .or_else(|| v.base10_parse::<i64>().ok().map(Value::from)),
+            Lit::Float(v) => v.base10_parse::<f64>().ok().map(Value::from),
+            _ => None,
+        },
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => {
+            match literal_json(&unary.expr)? {
+                Value::Number(v) => {
+                    if let Some(v) = v.as_i64() {
+                        Some(Value::from(-v))
+                    } else {
+                        v.as_f64().map(|v| Value::from(-v))
+                    }
+                }
+                _ => None,
+            }
+        }
+        Expr::MethodCall(call)
+            if call.args.is_empty()
+                && ["to_string", "to_owned", "into"]
+                    .contains(&call.method.to_string().as_str()) =>
+        {
+            literal_json(&call.receiver)
+        }
+        Expr::Call(call) if call.args.len() <= 1 => {
+            let Expr::Path(func) = call.func.as_ref() else {
+                return None;
+            };
+            let func = func
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::");
+            match (func.as_str(), call.args.first()) {
+                ("Some" | "String::from" | "Box::new", Some(arg)) => literal_json(arg),
+                _ => None,
+            }
+        }
+        Expr::Path(path) if path.path.is_ident("None") => Some(Value::Null),
+        Expr::Array(array) => array
+            .elems
+            .iter()
+            .map(literal_json)
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array),
+        Expr::Macro(mac) if mac.mac.path.is_ident("vec") => {
+            let items = mac
+                .mac
+                .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                .ok()?;
+            items
+                .iter()
+                .map(literal_json)
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array)
+        }
+        Expr::Paren(paren) => literal_json(&paren.expr),
+        Expr::Group(group) => literal_json(&group.expr),
+        _ => None,
+    }
+}
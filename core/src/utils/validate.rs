@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use syn::Ident;
+
+use super::combine_errors;
+use crate::tools::{DeriveBox, EnumValue, ExtraMacros, StructMembers, StructName, StructType};
+
+/// Check the parsed tree for conflicts that would otherwise only surface as
+/// confusing rustc errors inside the generated module.
+///
+/// Reports duplicate field names, duplicate variant names and named inline
+/// types that share a name, pointing at both occurrences, as well as invalid
+/// or duplicate protobuf numbers.
+pub(crate) fn validate(parent: &DeriveBox) -> syn::Result<()> {
+    let mut type_names = HashMap::new();
+    let mut errors = vec![];
+    validate_box(parent, &mut type_names, &mut errors);
+
+    combine_errors(errors)
+}
+
+fn validate_box(
+    parent: &DeriveBox,
+    type_names: &mut HashMap<String, Ident>,
+    errors: &mut Vec<syn::Error>,
+) {
+    match parent {
+        DeriveBox::Struct(parent) => {
+            check_type_name(&parent.ident, type_names, errors);
+            check_no_proto_number(&parent.extra_macros, errors);
+            validate_members(&parent.items, type_names, errors);
+        }
+        DeriveBox::Enum(parent) => {
+            check_type_name(&parent.ident, type_names, errors);
+            check_no_proto_number(&parent.extra_macros, errors);
+            check_duplicates(
+                parent.items.iter().map(|(key, _, _)| key),
+                "variant",
+                errors,
+            );
+
+            check_variant_proto_numbers(&parent.items, errors);
+
+            for (_, value, _) in parent.items.iter() {
+                match value {
+                    EnumValue::Empty => {}
+                    EnumValue::Tuple(v) => {
+                        for (ty, _) in v.iter() {
+                            validate_type(ty, type_names, errors);
+                        }
+                    }
+                    EnumValue::Struct(v) => validate_members(v, type_names, errors),
+                }
+            }
+        }
+    }
+}
+
+fn validate_members(
+    members: &StructMembers,
+    type_names: &mut HashMap<String, Ident>,
+    errors: &mut Vec<syn::Error>,
+) {
+    check_duplicates(members.iter().map(|(key, _, _, _, _)| key), "field", errors);
+    check_proto_numbers(members, errors);
+
+    for (_, ty, _, _, _) in members.iter() {
+        validate_type(ty, type_names, errors);
+    }
+}
+
+fn validate_type(
+    ty: &StructType,
+    type_names: &mut HashMap<String, Ident>,
+    errors: &mut Vec<syn::Error>,
+) {
+    match ty {
+        StructType::Static(_) => {}
+        StructType::InlineStruct(v) => {
+            validate_box(&DeriveBox::Struct(v.clone()), type_names, errors);
+        }
+        StructType::InlineEnum(v) => {
+            validate_box(&DeriveBox::Enum(v.clone()), type_names, errors);
+        }
+    }
+}
+
+fn check_type_name(
+    name: &StructName,
+    type_names: &mut HashMap<String, Ident>,
+    errors: &mut Vec<syn::Error>,
+) {
+    if let StructName::Named(ident) = name {
+        if let Some(first) = type_names.get(&ident.to_string()) {
+            errors.push(syn::Error::new(
+                ident.span(),
+                format!("the type `{}` is defined more than once", ident),
+            ));
+            errors.push(syn::Error::new(
+                first.span(),
+                format!("`{}` is first defined here", ident),
+            ));
+        } else {
+            type_names.insert(ident.to_string(), ident.clone());
+        }
+    }
+}
+
+fn check_duplicates<'a>(
+    keys: impl Iterator<Item = &'a Ident>,
+    kind: &str,
+    errors: &mut Vec<syn::Error>,
+) {
+    let mut seen: HashMap<String, &Ident> = HashMap::new();
+    for key in keys {
+        if let Some(first) = seen.get(&key.to_string()) {
+            errors.push(syn::Error::new(
+                key.span(),
+                format!("the {} `{}` is defined more than once", kind, key),
+            ));
+            errors.push(syn::Error::new(
+                first.span(),
+                format!("`{}` is first defined here", key),
+            ));
+        } else {
+            seen.insert(key.to_string(), key);
+        }
+    }
+}
+
+/// Fields without `#[yuuka(proto = N)]` are numbered by their position,
+/// starting from 1. Such a number changes when a field is inserted before
+/// it, so the fields of a type are numbered either all by position or all
+/// explicitly.
+fn check_proto_numbers(members: &StructMembers, errors: &mut Vec<syn::Error>) {
+    let items = members
+        .iter()
+        .map(|(key, _, _, _, extra_macros)| (key, extra_macros))
+        .collect::<Vec<_>>();
+    check_numbers(&items, ProtoNumbering::Fields, errors);
+}
+
+/// Variants are numbered like fields. The variants of a unit-only enum are
+/// the values of a protobuf enum, which start from 0, while the others are
+/// the alternatives of a `oneof`, which follow the rules of field numbers.
+fn check_variant_proto_numbers(
+    variants: &[(Ident, EnumValue, ExtraMacros)],
+    errors: &mut Vec<syn::Error>,
+) {
+    let numbering = if variants
+        .iter()
+        .all(|(_, value, _)| matches!(value, EnumValue::Empty))
+    {
+        ProtoNumbering::EnumValues
+    } else {
+        ProtoNumbering::Variants
+    };
+    let items = variants
+        .iter()
+        .map(|(key, _, extra_macros)| (key, extra_macros))
+        .collect::<Vec<_>>();
+    check_numbers(&items, numbering, errors);
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ProtoNumbering {
+    Fields,
+    Variants,
+    EnumValues,
+}
+
+fn check_numbers(
+    items: &[(&Ident, &ExtraMacros)],
+    numbering: ProtoNumbering,
+    errors: &mut Vec<syn::Error>,
+) {
+    let (items_name, numbers_name) = match numbering {
+        ProtoNumbering::Fields => ("fields", "field numbers"),
+        ProtoNumbering::Variants | ProtoNumbering::EnumValues => ("variants", "numbers"),
+    };
+
+    let explicit = items
+        .iter()
+        .any(|(_, extra_macros)| extra_macros.proto_number.is_some());
+    if explicit {
+        for (key, extra_macros) in items {
+            if extra_macros.proto_number.is_none() {
+                errors.push(syn::Error::new(
+                    key.span(),
+                    format!(
+                        "`{}` needs `#[yuuka(proto = N)]` as well, since other {} \
+                         of this type set their protobuf {}",
+                        key, items_name, numbers_name
+                    ),
+                ));
+            }
+        }
+    }
+
+    let mut seen: HashMap<u32, &Ident> = HashMap::new();
+    for (index, (key, extra_macros)) in items.iter().enumerate() {
+        let number = match &extra_macros.proto_number {
+            Some(lit) if numbering == ProtoNumbering::EnumValues => {
+                match lit.base10_parse::<u32>() {
+                    Ok(number) if number <= i32::MAX as u32 => number,
+                    _ => {
+                        errors.push(syn::Error::new(
+                            lit.span(),
+                            "protobuf enum values must be between 0 and 2147483647",
+                        ));
+                        continue;
+                    }
+                }
+            }
+            Some(lit) => match lit.base10_parse::<u32>() {
+                Ok(number) if (1..=536_870_911).contains(&number) => {
+                    if (19_000..=19_999).contains(&number) {
+                        errors.push(syn::Error::new(
+                            lit.span(),
+                            "protobuf field numbers 19000 to 19999 are reserved",
+                        ));
+                    }
+                    number
+                }
+                _ => {
+                    errors.push(syn::Error::new(
+                        lit.span(),
+                        "protobuf field numbers must be between 1 and 536870911",
+                    ));
+                    continue;
+                }
+            },
+            None if numbering == ProtoNumbering::EnumValues => index as u32,
+            None => index as u32 + 1,
+        };
+
+        if let Some(first) = seen.get(&number) {
+            errors.push(syn::Error::new(
+                key.span(),
+                format!(
+                    "the protobuf {} {} of `{}` is already used by `{}`",
+                    match numbering {
+                        ProtoNumbering::Fields | ProtoNumbering::Variants => "field number",
+                        ProtoNumbering::EnumValues => "enum value",
+                    },
+                    number,
+                    key,
+                    first
+                ),
+            ));
+        } else {
+            seen.insert(number, *key);
+        }
+    }
+}
+
+fn check_no_proto_number(extra_macros: &ExtraMacros, errors: &mut Vec<syn::Error>) {
+    if let Some(lit) = &extra_macros.proto_number {
+        errors.push(syn::Error::new(
+            lit.span(),
+            "`proto` sets the number of a field and cannot be used here",
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+use proc_macro2::{TokenStream, TokenTree};
+use yuuka_core::build::{Codegen, Error};
+
+const CONFIG: &str = r#"
+    #[derive(PartialEq)]
+    pub Config {
+        /// The title of the window.
+        title: String = "Untitled".to_string(),
+        window?: Window {
+            width: u32 = 1280,
+        },
+        plugins: [Plugin {
+            name: String,
+            enabled: bool = true,
+        }],
+        mode: enum Mode {
+            Dev,
+            Prod(i32),
+        } = Dev,
+    }
+"#;
+
+const COMMAND: &str = r#"
+    pub enum Command {
+        Quit,
+        Move { x: i32, y: i32 },
+    } = Quit
+"#;
+
+/// Every token, leaving out how punctuation is spaced.
+fn token_list(tokens: TokenStream) -> Vec<String> {
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            TokenTree::Group(group) => {
+                let mut list = vec![format!("{:?}", group.delimiter())];
+                list.extend(token_list(group.stream()));
+                list.push("end".to_string());
+                list
+            }
+            TokenTree::Punct(punct) => vec![punct.as_char().to_string()],
+            token => vec![token.to_string()],
+        })
+        .collect()
+}
+
+/// The generated source without its header, as tokens.
+fn source_tokens(source: &str) -> Vec<String> {
+    let (header, body) = source.split_once('\n').unwrap();
+    assert!(header.starts_with("// Generated by yuuka"));
+    token_list(body.parse().unwrap())
+}
+
+#[test]
+fn generate_matches_macros() {
+    let source = Codegen::new()
+        .rustfmt(false)
+        .derive_struct(CONFIG)
+        .derive_enum(COMMAND)
+        .generate()
+        .unwrap();
+
+    let mut expanded = yuuka_core::derive_struct(CONFIG.parse().unwrap());
+    expanded.extend(yuuka_core::derive_enum(COMMAND.parse().unwrap()));
+    assert_eq!(source_tokens(&source), token_list(expanded));
+}
+
+#[test]
+fn generate_readable() {
+    let source = Codegen::new()
+        .rustfmt(false)
+        .derive_struct(CONFIG)
+        .generate()
+        .unwrap();
+
+    assert!(source.contains(
+        "    pub struct Config {\n        /// The title of the window.\n        pub title: String,\n"
+    ));
+    assert!(source.contains("    macro_rules! __auto_Config {\n"));
+    assert!(source.contains("$($val:tt)+"));
+}
+
+#[test]
+fn generate_errors() {
+    let err = Codegen::new()
+        .derive_struct("pub Config {\n    a: String,\n    a: u32,\n}")
+        .derive_enum("enum Mode { Dev, }")
+        .derive_struct("Broken { a: }")
+        .derive_struct("Unclosed {\n    a: String = \"a,\n}")
+        .generate()
+        .unwrap_err();
+
+    let Error::Definition(errors) = &err else {
+        panic!("unexpected error: {}", err);
+    };
+    assert_eq!(
+        errors,
+        &[
+            "<string>:3:5: the field `a` is defined more than once",
+            "<string>:2:5: `a` is first defined here",
+            "<string>:1:13: unexpected end of input, expected identifier",
+            "<string>:2:17: unterminated string",
+        ]
+    );
+}
+
+#[test]
+fn write_file() {
+    let path = std::env::temp_dir().join(format!("yuuka-build-{}.rs", std::process::id()));
+    Codegen::new()
+        .derive_struct_file("tests/fixtures/config.yuuka")
+        .write_to(&path)
+        .unwrap();
+
+    let source = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(source
+        .starts_with("// Generated by yuuka from `tests/fixtures/config.yuuka`. Do not edit.\n"));
+    assert!(source.contains("pub struct Server"));
+
+    let err = Codegen::new()
+        .derive_struct_file("tests/fixtures/missing.yuuka")
+        .generate()
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(..)));
+}
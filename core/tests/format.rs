@@ -0,0 +1,311 @@
+use yuuka_core::format::{format_definition, format_rust, Error};
+
+/// Format some source, checking that formatting it again changes nothing.
+fn format(source: &str) -> String {
+    let formatted = format_rust(source).unwrap();
+    assert_eq!(format_rust(&formatted).unwrap(), formatted);
+    formatted
+}
+
+#[test]
+fn indentation() {
+    let source = r#"
+fn main() {
+    derive_struct!(
+        #[derive(PartialEq, Serialize, Deserialize)]
+        Config {
+          port: u16,
+          services: [Service {
+            domain: Vec < String >,
+            rules: [Rule {
+              pattern: String,
+              method: enum Method {
+                Redirect { url: String },
+                Proxy {
+                  host: String
+                },
+              } = Proxy { host: String::new() }
+            }]
+          }]
+        }
+    );
+}
+"#;
+    assert_eq!(
+        format(source),
+        r#"
+fn main() {
+    derive_struct!(
+        #[derive(PartialEq, Serialize, Deserialize)]
+        Config {
+            port: u16,
+            services: [Service {
+                domain: Vec<String>,
+                rules: [Rule {
+                    pattern: String,
+                    method: enum Method {
+                        Redirect { url: String },
+                        Proxy { host: String },
+                    } = Proxy { host: String::new() },
+                }],
+            }],
+        }
+    );
+}
+"#
+    );
+}
+
+#[test]
+fn comments_and_blank_lines() {
+    let source = r#"
+derive_struct!(Root {
+    // The name.
+    name: String, // Shown in the title.
+
+    /// The port.
+    port ?: u16 = 8080 /* the default */,
+    server: { /* inline */ host: String }
+});
+"#;
+    assert_eq!(
+        format(source),
+        r#"
+derive_struct!(Root {
+    // The name.
+    name: String, // Shown in the title.
+
+    /// The port.
+    port?: u16 = 8080 /* the default */,
+    server: { /* inline */
+        host: String,
+    },
+});
+"#
+    );
+}
+
+#[test]
+fn enums() {
+    let source = r#"
+derive_enum!(
+    #[derive(PartialEq)]
+    pub enum Command {
+        Quit,
+        Move { x: i32, y: i32 },
+        Say(String, [enum { Loud, Quiet }]),
+        Wait(Duration { seconds: u64 })
+    } = Quit
+);
+"#;
+    assert_eq!(
+        format(source),
+        r#"
+derive_enum!(
+    #[derive(PartialEq)]
+    pub enum Command {
+        Quit,
+        Move { x: i32, y: i32 },
+        Say(
+            String,
+            [enum {
+                Loud,
+                Quiet,
+            }],
+        ),
+        Wait(Duration {
+            seconds: u64,
+        }),
+    } = Quit
+);
+"#
+    );
+}
+
+#[test]
+fn long_attributes() {
+    let source = r#"
+derive_struct!(
+    #[yuuka(derive(Serialize, Deserialize), recursive_field(wrapper = option, serde(skip_serializing_if = "Option::is_none")))]
+    Root {
+        #[serde(rename = "a")] a: String,
+        b?: [String] = Some(vec![]),
+    }
+);
+"#;
+    assert_eq!(
+        format(source),
+        r#"
+derive_struct!(
+    #[yuuka(
+        derive(Serialize, Deserialize),
+        recursive_field(wrapper = option, serde(skip_serializing_if = "Option::is_none"))
+    )]
+    Root {
+        #[serde(rename = "a")]
+        a: String,
+        b?: [String] = Some(vec![]),
+    }
+);
+"#
+    );
+}
+
+#[test]
+fn optional_commas() {
+    let source = "derive_struct!(Root {\n    a: { b: u8 } c: u8\n});";
+    assert_eq!(
+        format(source),
+        "derive_struct!(Root {\n    a: {\n        b: u8,\n    },\n    c: u8,\n});"
+    );
+}
+
+#[test]
+fn rest_of_the_file() {
+    // Strings, comments, other macros and `macro_rules!` bodies are left alone.
+    let source = r#"
+// derive_struct!(Root { a: u8 });
+const TEXT: &str = "derive_struct!(Root { a: u8 })";
+
+macro_rules! define {
+    ($name:ident) => {
+        derive_struct!($name { a: u8 });
+    };
+}
+
+fn main() {
+    let a = auto!(Root { a: 1 });
+    yuuka::derive_struct!(Root { a: u8, b: u8 });
+    yuuka::derive_struct!(Other {
+        a: u8 });
+}
+"#;
+    assert_eq!(
+        format(source),
+        source.replace(
+            "yuuka::derive_struct!(Other {\n        a: u8 });",
+            "yuuka::derive_struct!(Other {\n        a: u8,\n    });"
+        )
+    );
+}
+
+#[test]
+fn one_line_calls() {
+    // Calls written on one line stay there while they fit.
+    let source = r#"
+fn main() {
+    derive_enum!(enum E{A,B}=A);
+    derive_struct!(#[derive(Debug)] Root { a ?: Vec < u8 >, b: [Item { c: u8 }] });
+    derive_struct!(Config { title: String = "Untitled".to_string(), servers: [Server { host: String, port: u16 = 8080 }] });
+}
+"#;
+    assert_eq!(
+        format(source),
+        r#"
+fn main() {
+    derive_enum!(enum E { A, B } = A);
+    derive_struct!(#[derive(Debug)] Root { a?: Vec<u8>, b: [Item { c: u8 }] });
+    derive_struct!(Config {
+        title: String = "Untitled".to_string(),
+        servers: [Server {
+            host: String,
+            port: u16 = 8080,
+        }],
+    });
+}
+"#
+    );
+}
+
+#[test]
+fn brace_calls() {
+    // The spaces inside the braces of the call are kept.
+    let source = r#"
+yuuka::derive_struct! { Other { a: u8 } }
+yuuka::derive_struct! { Other {
+    a: u8 } }
+yuuka::derive_struct! {
+    Other {
+        a: u8
+    }
+}
+"#;
+    assert_eq!(
+        format(source),
+        r#"
+yuuka::derive_struct! { Other { a: u8 } }
+yuuka::derive_struct! { Other {
+    a: u8,
+} }
+yuuka::derive_struct! {
+    Other {
+        a: u8,
+    }
+}
+"#
+    );
+}
+
+#[test]
+fn trailing_block_comments() {
+    // Block comments stay where they were written, and line comments go
+    // after the comma.
+    let source = r#"
+derive_struct!(Root {
+    a: u8 /* one */,
+    b: u8 = 1 /* two */ /* three */, // four
+    c: { d: u8 } /* five */
+    e: u8 // six
+});
+"#;
+    assert_eq!(
+        format(source),
+        r#"
+derive_struct!(Root {
+    a: u8 /* one */,
+    b: u8 = 1 /* two */ /* three */, // four
+    c: {
+        d: u8,
+    } /* five */,
+    e: u8, // six
+});
+"#
+    );
+}
+
+#[test]
+fn definition_files() {
+    let source = "// The configuration.\npub Config {\n  name: String,\n  server: Server { host: String = \"localhost\".to_string() }\n}";
+    let formatted = format_definition(source).unwrap();
+    assert_eq!(
+        formatted,
+        r#"// The configuration.
+pub Config {
+    name: String,
+    server: Server {
+        host: String = "localhost".to_string(),
+    },
+}
+"#
+    );
+    assert_eq!(format_definition(&formatted).unwrap(), formatted);
+    assert_eq!(format_definition("\n").unwrap(), "");
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        format_rust("fn main() {\n    derive_struct!(Root { a: String = \"a });\n}\n").unwrap_err(),
+        Error {
+            line: 2,
+            column: 39,
+            message: "unterminated string".to_string()
+        }
+    );
+    assert_eq!(
+        format_definition("Root {\n    a: (u8,\n}")
+            .unwrap_err()
+            .to_string(),
+        "3:1: mismatched closing delimiter"
+    );
+}
@@ -0,0 +1,102 @@
+use yuuka_core::inspect::{inspect_definition, inspect_rust, Error};
+
+#[test]
+fn rust_source() {
+    let source = r#"
+use yuuka::{derive_enum, derive_struct};
+
+derive_struct!(Config {
+    server: {
+        tls?: { cert: String },
+    },
+    services: [Service {
+        rules: [{ pattern: String }],
+    }],
+});
+
+fn main() {
+    derive_enum!(
+        #[derive(PartialEq)]
+        enum Command {
+            Quit,
+            Move(enum { Up, Down }),
+            Say { tone: enum { Loud, Quiet } },
+        } = Quit
+    );
+}
+"#;
+    let inspections = inspect_rust(source).unwrap();
+    assert_eq!(inspections.len(), 2);
+
+    let config = &inspections[0];
+    assert_eq!((config.root.as_str(), config.line), ("Config", 4));
+    assert_eq!(
+        config.anonymous,
+        [
+            ("_Config_0_anonymous", "Config.server"),
+            ("_Config_1_anonymous", "Config.server.tls"),
+            ("_Config_3_anonymous", "Config.services.rules"),
+        ]
+        .map(|(name, path)| (name.to_string(), path.to_string()))
+    );
+    for item in [
+        "mod __Config",
+        "pub struct _Config_1_anonymous",
+        "pub tls: Option<_Config_1_anonymous>,",
+        "macro_rules! __auto_Config",
+        "macro_rules! __ty_Service",
+    ] {
+        assert!(config.source.contains(item), "missing {}", item);
+    }
+
+    let command = &inspections[1];
+    assert_eq!((command.root.as_str(), command.line), ("Command", 14));
+    assert_eq!(
+        command.anonymous,
+        [
+            ("_Command_0_anonymous", "Command::Move.0"),
+            ("_Command_1_anonymous", "Command::Say.tone"),
+        ]
+        .map(|(name, path)| (name.to_string(), path.to_string()))
+    );
+    assert!(command.source.contains("impl Default for Command"));
+}
+
+#[test]
+fn definition_files() {
+    let inspection =
+        inspect_definition("// A mode.\n#[derive(PartialEq)]\npub enum Mode { Dev, Prod } = Dev\n")
+            .unwrap();
+    assert_eq!((inspection.root.as_str(), inspection.line), ("Mode", 1));
+    assert!(inspection.anonymous.is_empty());
+    assert!(inspection.source.contains("pub mod __Mode"));
+    assert!(inspection.source.contains("pub enum Mode"));
+
+    let inspection = inspect_definition("Root { named: Named { a: u8 } }").unwrap();
+    assert!(inspection.anonymous.is_empty());
+    assert!(inspection.source.contains("pub struct Named"));
+}
+
+#[test]
+fn errors() {
+    // Positions are in the file, not in the macro input.
+    assert_eq!(
+        inspect_rust("fn main() {\n    derive_struct!(Root {\n        a: Vec<u8\n    });\n}\n")
+            .unwrap_err(),
+        Error {
+            line: 4,
+            column: 5,
+            message: "expected `,`".to_string()
+        }
+    );
+    assert_eq!(
+        inspect_rust("derive_struct!(Root { a: u8, a: u8 });")
+            .unwrap_err()
+            .to_string(),
+        "1:30: the field `a` is defined more than once"
+    );
+    assert_eq!(
+        inspect_definition("\n\n{ a: u8 }").unwrap_err().to_string(),
+        "3:1: unnamed root struct is not supported; provide a name for the struct"
+    );
+}
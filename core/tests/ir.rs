@@ -0,0 +1,197 @@
+use serde_json::json;
+use yuuka_core::ir::{DefaultValue, Definition, TypeKind, VariantFields, Wrapper};
+
+const CONFIG: &str = r#"
+    #[derive(PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub Config {
+        /// The title of the window.
+        title: String = "Untitled".to_string(),
+        window?: {
+            width: u32 = 1280,
+        },
+        plugins: [Plugin {
+            name: String,
+            enabled: bool = true,
+        }] = vec![Plugin { name: "core".to_string(), enabled: true }],
+        tags: HashMap<String, Vec<String>>,
+    }
+"#;
+
+const COMMAND: &str = r#"
+    #[macro_export]
+    pub enum Command {
+        Quit,
+        Move { x: i32, y?: i32 },
+        Say(String, [enum { Loud, Quiet }]),
+    } = Quit
+"#;
+
+#[test]
+fn struct_definition() {
+    let definition = Definition::from_struct(CONFIG.parse().unwrap()).unwrap();
+    assert_eq!(definition.root, "Config");
+    assert!(definition.public);
+    assert!(!definition.macros_public);
+    assert!(definition.enums.is_empty());
+
+    let names = definition
+        .structs
+        .iter()
+        .map(|v| (v.name.as_str(), v.anonymous))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            ("_Config_0_anonymous", true),
+            ("Plugin", false),
+            ("Config", false)
+        ]
+    );
+
+    let config = definition.structs.last().unwrap();
+    assert_eq!(config.derives, ["PartialEq", "Serialize"]);
+    assert_eq!(config.attributes, [r#"serde(rename_all = "camelCase")"#]);
+
+    let title = &config.fields[0];
+    assert_eq!(title.name, "title");
+    assert_eq!(title.ty.rust, "String");
+    assert_eq!(title.ty.kind, TypeKind::Static);
+    assert_eq!(
+        title.default,
+        DefaultValue::Single(r#""Untitled".to_string()"#.to_string())
+    );
+    assert_eq!(title.attributes, [r#"doc = " The title of the window.""#]);
+
+    let window = &config.fields[1];
+    assert_eq!(window.ty.rust, "Option<_Config_0_anonymous>");
+    assert_eq!(window.ty.inner, "_Config_0_anonymous");
+    assert_eq!(window.ty.kind, TypeKind::InlineStruct);
+    assert_eq!(window.ty.wrapper, Wrapper::Option);
+
+    let plugins = &config.fields[2];
+    assert_eq!(plugins.ty.rust, "Vec<Plugin>");
+    assert_eq!(plugins.ty.wrapper, Wrapper::Vec);
+    assert_eq!(
+        plugins.default,
+        DefaultValue::Single(
+            r#"vec![Plugin { name: "core".to_string(), enabled: true }]"#.to_string()
+        )
+    );
+
+    assert_eq!(config.fields[3].ty.rust, "HashMap<String, Vec<String>>");
+}
+
+#[test]
+fn enum_definition() {
+    let definition = Definition::from_enum(COMMAND.parse().unwrap()).unwrap();
+    assert_eq!(definition.root, "Command");
+    assert!(definition.macros_public);
+    assert!(definition.structs.is_empty());
+
+    let command = definition.enums.last().unwrap();
+    assert_eq!(command.name, "Command");
+    assert_eq!(command.default.as_deref(), Some("Self::Quit"));
+    assert_eq!(command.variants[0].fields, VariantFields::Unit);
+
+    let VariantFields::Struct(fields) = &command.variants[1].fields else {
+        panic!("`Move` should be a struct variant");
+    };
+    assert_eq!(fields[1].name, "y");
+    assert_eq!(fields[1].ty.rust, "Option<i32>");
+
+    let VariantFields::Tuple(types) = &command.variants[2].fields else {
+        panic!("`Say` should be a tuple variant");
+    };
+    assert_eq!(types[0].rust, "String");
+    assert_eq!(types[1].kind, TypeKind::InlineEnum);
+    assert_eq!(types[1].wrapper, Wrapper::Vec);
+    assert!(definition.enums.iter().any(|v| v.name == types[1].inner));
+}
+
+#[test]
+fn json() {
+    let definition = Definition::from_enum(
+        r#"
+        enum Mode {
+            Dev,
+            Prod(u8),
+        } = Dev
+        "#
+        .parse()
+        .unwrap(),
+    )
+    .unwrap();
+
+    let value = serde_json::to_value(&definition).unwrap();
+    assert_eq!(
+        value["enums"][0]["variants"],
+        json!([
+            {
+                "name": "Dev",
+                "fields": "unit",
+                "proto_number": null,
+                "attributes": []
+            },
+            {
+                "name": "Prod",
+                "fields": {
+                    "tuple": [{
+                        "rust": "u8",
+                        "inner": "u8",
+                        "kind": "static",
+                        "wrapper": "plain",
+                        "proto_number": null
+                    }]
+                },
+                "proto_number": null,
+                "attributes": []
+            }
+        ])
+    );
+    assert_eq!(value["enums"][0]["features"]["schema"], json!(false));
+
+    let text = serde_json::to_string(&definition).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Definition>(&text).unwrap(),
+        definition
+    );
+}
+
+#[test]
+fn expand_matches_macros() {
+    let definition = Definition::from_struct(CONFIG.parse().unwrap()).unwrap();
+    assert_eq!(
+        definition.expand().unwrap().to_string(),
+        yuuka_core::derive_struct(CONFIG.parse().unwrap()).to_string()
+    );
+
+    let definition = Definition::from_enum(COMMAND.parse().unwrap()).unwrap();
+    let text = serde_json::to_string(&definition).unwrap();
+    let definition = serde_json::from_str::<Definition>(&text).unwrap();
+    assert_eq!(
+        definition.expand().unwrap().to_string(),
+        yuuka_core::derive_enum(COMMAND.parse().unwrap()).to_string()
+    );
+}
+
+#[test]
+fn expand_edited() {
+    let mut definition = Definition::from_struct(CONFIG.parse().unwrap()).unwrap();
+    definition.structs[1].fields[1].default = DefaultValue::Single("false".to_string());
+    let expanded = definition.expand().unwrap().to_string();
+    assert!(expanded.contains("enabled : false"));
+
+    definition.structs[1].fields[0].ty.rust = "Vec<".to_string();
+    let err = definition.expand().unwrap_err();
+    assert!(err.to_string().starts_with("`Vec<`: "));
+}
+
+#[test]
+fn errors() {
+    let err = Definition::from_struct("Root { a: }".parse().unwrap()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unexpected end of input, expected identifier"
+    );
+}
@@ -6,25 +6,25 @@ fn main() -> Result<()> {
     derive_struct!(
         #[derive(PartialEq, Serialize, Deserialize)]
         LanguagePack {
-          是: String,
-          否: String,
-          确认: String,
-          取消: String,
-          保存: String,
-          主页: {
-            启动: String,
-            设置: String,
-          },
-          设置: {
-            虚拟机路径: String,
-            程序本体路径: String,
-            网络配置: {
-              网络配置: String,
-              是否启用代理: String,
-              代理地址: String,
-              是否启用IPV6: String,
-            }
-          }
+            是: String,
+            否: String,
+            确认: String,
+            取消: String,
+            保存: String,
+            主页: {
+                启动: String,
+                设置: String,
+            },
+            设置: {
+                虚拟机路径: String,
+                程序本体路径: String,
+                网络配置: {
+                    网络配置: String,
+                    是否启用代理: String,
+                    代理地址: String,
+                    是否启用IPV6: String,
+                },
+            },
         }
     );
 
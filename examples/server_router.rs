@@ -6,19 +6,19 @@ fn main() -> Result<()> {
     derive_struct!(
         #[derive(PartialEq, Serialize, Deserialize)]
         Config {
-          port: u16,
-          services: [Service {
-            domain: Vec<String>,
-            rules: [Rule {
-              pattern: String,
-              method: enum Method {
-                Redirect { url: String },
-                Proxy { host: String },
-                StaticFile { path: String },
-                StaticDir { path: String },
-              } = Proxy { host: String::new() }
-            }]
-          }]
+            port: u16,
+            services: [Service {
+                domain: Vec<String>,
+                rules: [Rule {
+                    pattern: String,
+                    method: enum Method {
+                        Redirect { url: String },
+                        Proxy { host: String },
+                        StaticFile { path: String },
+                        StaticDir { path: String },
+                    } = Proxy { host: String::new() },
+                }],
+            }],
         }
     );
 
@@ -0,0 +1,85 @@
+use proc_macro::TokenStream;
+
+/// Generate nested structs from a concise DSL-like syntax.
+///
+/// Supports inline struct definitions, arrays, enums, Option-wrapped fields,
+/// custom derives, visibility control, and default values. See the crate-level
+/// documentation for full syntax and examples.
+#[proc_macro]
+pub fn derive_struct(input: TokenStream) -> TokenStream {
+    yuuka_core::derive_struct(input.into()).into()
+}
+
+/// Generate nested structs by inferring their shape from a sample JSON file.
+///
+/// The path is relative to the root of the crate being compiled. Objects
+/// become inline structs, arrays become `[...]` or `Vec<T>` fields, and keys
+/// that are `null` or missing in some array items become `?` fields. The
+/// result goes through the same pipeline as `derive_struct`.
+#[proc_macro]
+pub fn derive_struct_from_json(input: TokenStream) -> TokenStream {
+    yuuka_core::derive_struct_from_json(input.into()).into()
+}
+
+/// Generate nested structs from a JSON Schema file.
+///
+/// The path is relative to the root of the crate being compiled. Objects
+/// become inline structs, `required` decides between plain and `?` fields,
+/// `enum` and `oneOf` become inline enums, `default` values become DSL
+/// defaults, and `$defs` referenced with `$ref` become named types.
+#[proc_macro]
+pub fn derive_struct_from_json_schema(input: TokenStream) -> TokenStream {
+    yuuka_core::derive_struct_from_json_schema(input.into()).into()
+}
+
+/// Generate enums (and associated structs) from a concise DSL-like syntax.
+///
+/// Supports unit variants, tuple variants, and struct variants with inline
+/// member definitions. Default values, custom derives, and visibility are
+/// controlled via the same syntax as `derive_struct`.
+#[proc_macro]
+pub fn derive_enum(input: TokenStream) -> TokenStream {
+    yuuka_core::derive_enum(input.into()).into()
+}
+
+/// Generate nested structs from a `derive_struct` definition kept in a file.
+///
+/// The path is relative to the root of the crate being compiled, and the
+/// file holds what would go between the parentheses of `derive_struct!`.
+/// Errors in the file are reported with its line and column.
+#[proc_macro]
+pub fn derive_struct_file(input: TokenStream) -> TokenStream {
+    yuuka_core::derive_struct_file(input.into()).into()
+}
+
+/// Generate enums from a `derive_enum` definition kept in a file, like
+/// `derive_struct_file`.
+#[proc_macro]
+pub fn derive_enum_file(input: TokenStream) -> TokenStream {
+    yuuka_core::derive_enum_file(input.into()).into()
+}
+
+/// Construct an instance of a type generated by `derive_struct` or
+/// `derive_enum` using a minimal value-only syntax.
+///
+/// Each field value is automatically routed to the correct generated
+/// `__auto_*` helper macro so that nested inline types are constructed
+/// transparently.
+#[proc_macro]
+pub fn auto(input: TokenStream) -> TokenStream {
+    yuuka_core::auto(input.into()).into()
+}
+
+/// Resolve a field path on a type generated by `derive_struct` or
+/// `derive_enum` to the concrete type declared at that position.
+///
+/// `ty!(Root.nick_name.chinese)` names the type of `Root::nick_name::chinese`
+/// no matter whether it is anonymous or not, so `impl` blocks and function
+/// signatures do not depend on generated names. Enum variants are addressed by
+/// name, and tuple fields by index (`ty!(Root.a.Variant.0)`). The `Option` and
+/// `Vec` wrappers introduced by `?` and `[...]` are stripped. Paths end at
+/// types written in the DSL.
+#[proc_macro]
+pub fn ty(input: TokenStream) -> TokenStream {
+    yuuka_core::ty(input.into()).into()
+}
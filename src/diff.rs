@@ -0,0 +1,111 @@
+//! Structural comparison behind the `diff` method generated by
+//! `#[yuuka(diff)]`.
+
+use std::fmt::{self, Debug, Display};
+
+/// A leaf that differs between two values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// Where the leaf is, like `services[1].rules[0].method.Proxy.host`. It is
+    /// empty when the compared values differ as a whole.
+    pub path: String,
+    /// The `Debug` output of the old value, `None` if the leaf was added.
+    pub before: Option<String>,
+    /// The `Debug` output of the new value, `None` if the leaf was removed.
+    pub after: Option<String>,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "."
+        } else {
+            &self.path
+        };
+        let before = self.before.as_deref().unwrap_or("<absent>");
+        let after = self.after.as_deref().unwrap_or("<absent>");
+
+        write!(f, "{}: {} -> {}", path, before, after)
+    }
+}
+
+/// Types that can list the leaves that differ from another value.
+///
+/// It is implemented by every type generated with `#[yuuka(diff)]`, and by
+/// `Vec` and `Option` of such types.
+pub trait Diff {
+    /// Push the changes from `self` to `other` onto `changes`, with every path
+    /// prefixed by `path`.
+    fn diff_into(&self, other: &Self, path: &str, changes: &mut Vec<Change>);
+}
+
+impl<T: Diff + Debug> Diff for Vec<T> {
+    fn diff_into(&self, other: &Self, path: &str, changes: &mut Vec<Change>) {
+        for (index, (before, after)) in self.iter().zip(other.iter()).enumerate() {
+            before.diff_into(after, &index_path(path, index), changes);
+        }
+        for (index, before) in self.iter().enumerate().skip(other.len()) {
+            changes.push(Change {
+                path: index_path(path, index),
+                before: Some(format!("{:?}", before)),
+                after: None,
+            });
+        }
+        for (index, after) in other.iter().enumerate().skip(self.len()) {
+            changes.push(Change {
+                path: index_path(path, index),
+                before: None,
+                after: Some(format!("{:?}", after)),
+            });
+        }
+    }
+}
+
+impl<T: Diff + Debug> Diff for Option<T> {
+    fn diff_into(&self, other: &Self, path: &str, changes: &mut Vec<Change>) {
+        match (self, other) {
+            (Some(before), Some(after)) => before.diff_into(after, path, changes),
+            (None, None) => {}
+            _ => changed(self, other, path, changes),
+        }
+    }
+}
+
+/// Compare two values that are not split any further with `PartialEq`,
+/// reporting them through their `Debug` output when they differ.
+#[doc(hidden)]
+pub fn diff_leaf<T: PartialEq + Debug + ?Sized>(
+    before: &T,
+    after: &T,
+    path: &str,
+    changes: &mut Vec<Change>,
+) {
+    if before != after {
+        changed(before, after, path, changes);
+    }
+}
+
+/// Report two values that are known to differ, like two different variants
+/// of an enum.
+#[doc(hidden)]
+pub fn changed<T: Debug + ?Sized>(before: &T, after: &T, path: &str, changes: &mut Vec<Change>) {
+    changes.push(Change {
+        path: path.to_string(),
+        before: Some(format!("{:?}", before)),
+        after: Some(format!("{:?}", after)),
+    });
+}
+
+#[doc(hidden)]
+pub fn field_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+#[doc(hidden)]
+pub fn index_path(path: &str, index: usize) -> String {
+    format!("{}[{}]", path, index)
+}
@@ -0,0 +1,10 @@
+//! Support for the `example_json` method generated by `#[yuuka(example)]`.
+
+use serde::Serialize;
+
+pub use serde_json::Value;
+
+#[doc(hidden)]
+pub fn to_json<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).expect("failed to serialize the example")
+}
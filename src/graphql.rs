@@ -0,0 +1,249 @@
+//! GraphQL SDL mirroring the types generated with `#[yuuka(schema)]`.
+
+use crate::schema::{
+    split_type, FieldSchema, Schema, TypeRef, TypeSchema, VariantSchema, VariantShape, Wrapper,
+};
+
+/// GraphQL type definitions for `T` and every generated type nested in it,
+/// with matching input types when `inputs` is set.
+pub fn graphql<T: Schema>(inputs: bool) -> String {
+    T::schema().graphql(inputs)
+}
+
+impl TypeSchema {
+    /// GraphQL type definitions for this type and every generated type nested
+    /// in it, with matching input types when `inputs` is set.
+    ///
+    /// Structs become object types. Enums with only unit variants become
+    /// GraphQL enums, other enums become unions of one object type per
+    /// variant, named like `MethodProxy`.
+    pub fn graphql(&'static self, inputs: bool) -> String {
+        let mut context = Context {
+            queue: vec![self],
+            uses_json: false,
+        };
+        let mut defs = vec![];
+        let mut index = 0;
+        while let Some(schema) = context.queue.get(index).copied() {
+            defs.extend(output_defs(schema, &mut context));
+            if inputs {
+                defs.extend(input_def(schema, &mut context));
+            }
+            index += 1;
+        }
+        if context.uses_json {
+            defs.insert(0, "scalar JSON\n".to_string());
+        }
+        defs.join("\n")
+    }
+}
+
+struct Context {
+    queue: Vec<&'static TypeSchema>,
+    /// Whether a type without a GraphQL counterpart was mapped to `JSON`.
+    uses_json: bool,
+}
+
+fn is_unit_enum(schema: &TypeSchema) -> bool {
+    match schema {
+        TypeSchema::Struct(_) => false,
+        TypeSchema::Enum(v) => v
+            .variants
+            .iter()
+            .all(|variant| matches!(variant.shape, VariantShape::Unit)),
+    }
+}
+
+fn output_defs(schema: &'static TypeSchema, context: &mut Context) -> Vec<String> {
+    match schema {
+        TypeSchema::Struct(v) => vec![object("type", v.name, v.fields, false, context)],
+        TypeSchema::Enum(v) if is_unit_enum(schema) => {
+            let mut ret = format!("enum {} {{\n", v.name);
+            for variant in v.variants.iter().filter(|variant| !variant.serde_skip) {
+                ret.push_str(&format!("  {}\n", graphql_name(variant.serde_name)));
+            }
+            ret.push_str("}\n");
+            vec![ret]
+        }
+        TypeSchema::Enum(v) => {
+            let variants = v
+                .variants
+                .iter()
+                .filter(|variant| !variant.serde_skip)
+                .collect::<Vec<_>>();
+            let members = variants
+                .iter()
+                .map(|variant| variant_object_name(v.name, variant))
+                .collect::<Vec<_>>();
+
+            let mut ret = vec![format!("union {} = {}\n", v.name, members.join(" | "))];
+            for (variant, name) in variants.iter().zip(&members) {
+                ret.push(variant_object(name, variant, context));
+            }
+            ret
+        }
+    }
+}
+
+/// The object type wrapping the content of a variant.
+fn variant_object(name: &str, variant: &'static VariantSchema, context: &mut Context) -> String {
+    match variant.shape {
+        // Object types need at least one field.
+        VariantShape::Unit => format!("type {} {{\n  _: Boolean\n}}\n", name),
+        VariantShape::Tuple(items) => {
+            let mut ret = format!("type {} {{\n", name);
+            for (index, item) in items.iter().enumerate() {
+                let key = match items {
+                    [_] => "value".to_string(),
+                    _ => format!("_{}", index),
+                };
+                ret.push_str(&format!(
+                    "  {}: {}\n",
+                    key,
+                    field_type(item, false, context)
+                ));
+            }
+            ret.push_str("}\n");
+            ret
+        }
+        VariantShape::Struct(fields) => object("type", name, fields, false, context),
+    }
+}
+
+fn input_def(schema: &'static TypeSchema, context: &mut Context) -> Vec<String> {
+    match schema {
+        TypeSchema::Struct(v) => vec![object(
+            "input",
+            &format!("{}Input", v.name),
+            v.fields,
+            true,
+            context,
+        )],
+        // Enums are valid input types as they are.
+        TypeSchema::Enum(_) if is_unit_enum(schema) => vec![],
+        // There are no input unions, so every variant becomes a field of a
+        // `@oneOf` input object instead.
+        TypeSchema::Enum(v) => {
+            let mut defs = vec![];
+            let mut ret = format!("input {}Input @oneOf {{\n", v.name);
+            for variant in v.variants.iter().filter(|variant| !variant.serde_skip) {
+                let ty = match variant.shape {
+                    VariantShape::Unit => "Boolean".to_string(),
+                    VariantShape::Tuple([item]) => field_type(item, true, context),
+                    VariantShape::Tuple(items) => {
+                        let name = format!("{}Input", variant_object_name(v.name, variant));
+                        let mut def = format!("input {} {{\n", name);
+                        for (index, item) in items.iter().enumerate() {
+                            def.push_str(&format!(
+                                "  _{}: {}\n",
+                                index,
+                                field_type(item, true, context)
+                            ));
+                        }
+                        def.push_str("}\n");
+                        defs.push(def);
+                        name
+                    }
+                    VariantShape::Struct(fields) => {
+                        let name = format!("{}Input", variant_object_name(v.name, variant));
+                        defs.push(object("input", &name, fields, true, context));
+                        name
+                    }
+                };
+                let ty = ty.strip_suffix('!').unwrap_or(&ty);
+                ret.push_str(&format!("  {}: {}\n", graphql_name(variant.serde_name), ty));
+            }
+            ret.push_str("}\n");
+            defs.insert(0, ret);
+            defs
+        }
+    }
+}
+
+fn object(
+    keyword: &str,
+    name: &str,
+    fields: &'static [FieldSchema],
+    input: bool,
+    context: &mut Context,
+) -> String {
+    let mut ret = format!("{} {} {{\n", keyword, name);
+    for field in fields.iter().filter(|field| !field.serde_skip) {
+        ret.push_str(&format!(
+            "  {}: {}\n",
+            graphql_name(field.serde_name),
+            field_type(field, input, context)
+        ));
+    }
+    ret.push_str("}\n");
+    ret
+}
+
+fn variant_object_name(enum_name: &str, variant: &VariantSchema) -> String {
+    format!("{}{}", enum_name, variant.name)
+}
+
+/// The GraphQL type of a field, like `[Service!]!`.
+fn field_type(field: &'static FieldSchema, input: bool, context: &mut Context) -> String {
+    let ty = match field.inner {
+        TypeRef::Generated(schema) => {
+            let schema = schema();
+            if !context.queue.iter().any(|v| std::ptr::eq(*v, schema)) {
+                context.queue.push(schema);
+            }
+            if input && !is_unit_enum(schema) {
+                format!("{}Input!", schema.name())
+            } else {
+                format!("{}!", schema.name())
+            }
+        }
+        TypeRef::Static(ty) => static_type(ty, context),
+    };
+    match field.wrapper {
+        Wrapper::Plain => ty,
+        Wrapper::Vec => format!("[{}]!", ty),
+        Wrapper::Option => ty.strip_suffix('!').unwrap_or(&ty).to_string(),
+        Wrapper::OptionVec => format!("[{}]", ty),
+    }
+}
+
+/// The GraphQL type of a type written in the DSL, as far as it can be told
+/// from its source text. Unknown types become the `JSON` scalar.
+fn static_type(ty: &str, context: &mut Context) -> String {
+    let ty = ty.trim();
+    if let Some(ty) = ty.strip_prefix('&') {
+        let ty = ty.strip_prefix("'static").unwrap_or(ty);
+        return static_type(ty, context);
+    }
+
+    let (name, args) = split_type(ty);
+    match (name, args.as_slice()) {
+        ("String" | "str" | "char" | "PathBuf" | "Path", _) => "String!".to_string(),
+        ("bool", _) => "Boolean!".to_string(),
+        (
+            "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64" | "i128"
+            | "isize",
+            _,
+        ) => "Int!".to_string(),
+        ("f32" | "f64", _) => "Float!".to_string(),
+        ("Option", [item]) => {
+            let item = static_type(item, context);
+            item.strip_suffix('!').unwrap_or(&item).to_string()
+        }
+        ("Vec" | "VecDeque" | "LinkedList" | "HashSet" | "BTreeSet", [item]) => {
+            format!("[{}]!", static_type(item, context))
+        }
+        ("Box" | "Rc" | "Arc" | "Cow", [.., item]) => static_type(item, context),
+        _ => {
+            context.uses_json = true;
+            "JSON!".to_string()
+        }
+    }
+}
+
+/// GraphQL names only allow letters, digits and `_`.
+fn graphql_name(name: &str) -> String {
+    name.chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect()
+}
@@ -0,0 +1,238 @@
+//! JSON Schema (draft 2020-12) documents built from the static descriptions of
+//! `#[yuuka(schema)]`.
+
+use serde_json::{json, Map, Value};
+
+use crate::schema::{
+    split_type, FieldSchema, Schema, Tagging, TypeRef, TypeSchema, VariantSchema, VariantShape,
+    Wrapper,
+};
+
+/// The JSON Schema of `T`, with every generated type nested in it under
+/// `$defs`.
+pub fn json_schema<T: Schema>() -> Value {
+    T::schema().json_schema()
+}
+
+impl TypeSchema {
+    /// The JSON Schema of this type, with every generated type nested in it
+    /// under `$defs`.
+    pub fn json_schema(&'static self) -> Value {
+        let mut defs = Map::new();
+        let schema = type_schema(self, &mut defs);
+
+        let mut root = Map::new();
+        root.insert(
+            "$schema".to_string(),
+            json!("https://json-schema.org/draft/2020-12/schema"),
+        );
+        root.insert("title".to_string(), json!(self.name()));
+        if let Value::Object(schema) = schema {
+            root.extend(schema);
+        }
+        if !defs.is_empty() {
+            root.insert("$defs".to_string(), Value::Object(defs));
+        }
+        Value::Object(root)
+    }
+}
+
+fn type_schema(schema: &'static TypeSchema, defs: &mut Map<String, Value>) -> Value {
+    match schema {
+        TypeSchema::Struct(v) => object_schema(v.fields, v.deny_unknown_fields, defs),
+        TypeSchema::Enum(v) => {
+            let variants = v
+                .variants
+                .iter()
+                .filter(|variant| !variant.serde_skip)
+                .map(|variant| variant_schema(variant, v.tagging, defs))
+                .collect::<Vec<_>>();
+            if v.tagging == Tagging::Untagged {
+                json!({ "anyOf": variants })
+            } else {
+                json!({ "oneOf": variants })
+            }
+        }
+    }
+}
+
+fn object_schema(
+    fields: &'static [FieldSchema],
+    deny_unknown_fields: bool,
+    defs: &mut Map<String, Value>,
+) -> Value {
+    let mut properties = Map::new();
+    let mut required = vec![];
+    for field in fields.iter().filter(|field| !field.serde_skip) {
+        let mut schema = wrap(inner_schema(field.inner, defs), field.wrapper);
+        if let Some(default) = field
+            .default_json
+            .and_then(|v| serde_json::from_str(v).ok())
+        {
+            if let Value::Object(schema) = &mut schema {
+                schema.insert("default".to_string(), default);
+            } else {
+                schema = json!({ "allOf": [schema], "default": default });
+            }
+        }
+        properties.insert(field.serde_name.to_string(), schema);
+
+        // serde fills missing `Option`s in with `None`.
+        let is_option = matches!(field.wrapper, Wrapper::Option | Wrapper::OptionVec)
+            || field.ty.starts_with("Option<");
+        if !is_option && !field.serde_default {
+            required.push(field.serde_name);
+        }
+    }
+
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties,
+    });
+    if !required.is_empty() {
+        schema["required"] = json!(required);
+    }
+    if deny_unknown_fields {
+        schema["additionalProperties"] = json!(false);
+    }
+    schema
+}
+
+fn variant_schema(
+    variant: &'static VariantSchema,
+    tagging: Tagging,
+    defs: &mut Map<String, Value>,
+) -> Value {
+    let name = variant.serde_name;
+    let content = match &variant.shape {
+        VariantShape::Unit => None,
+        VariantShape::Tuple([item]) => Some(wrap(inner_schema(item.inner, defs), item.wrapper)),
+        VariantShape::Tuple(items) => {
+            let items = items
+                .iter()
+                .map(|item| wrap(inner_schema(item.inner, defs), item.wrapper))
+                .collect::<Vec<_>>();
+            Some(json!({
+                "type": "array",
+                "prefixItems": items,
+                "minItems": items.len(),
+                "maxItems": items.len(),
+            }))
+        }
+        VariantShape::Struct(fields) => Some(object_schema(fields, false, defs)),
+    };
+
+    match (tagging, content) {
+        (Tagging::External, None) => json!({ "const": name }),
+        (Tagging::External, Some(content)) => json!({
+            "type": "object",
+            "properties": { name: content },
+            "required": [name],
+            "additionalProperties": false,
+        }),
+        (Tagging::Internal { tag }, content) => {
+            let tag_schema = json!({
+                "type": "object",
+                "properties": { tag: { "const": name } },
+                "required": [tag],
+            });
+            match content {
+                None => tag_schema,
+                // The fields of a struct variant sit next to the tag.
+                Some(mut content) if matches!(variant.shape, VariantShape::Struct(_)) => {
+                    content["properties"][tag] = json!({ "const": name });
+                    let mut required = vec![json!(tag)];
+                    if let Some(Value::Array(list)) = content.get("required") {
+                        required.extend(list.iter().cloned());
+                    }
+                    content["required"] = json!(required);
+                    content
+                }
+                Some(content) => json!({ "allOf": [tag_schema, content] }),
+            }
+        }
+        (Tagging::Adjacent { tag, .. }, None) => json!({
+            "type": "object",
+            "properties": { tag: { "const": name } },
+            "required": [tag],
+        }),
+        (Tagging::Adjacent { tag, content: key }, Some(content)) => json!({
+            "type": "object",
+            "properties": { tag: { "const": name }, key: content },
+            "required": [tag, key],
+        }),
+        (Tagging::Untagged, None) => json!({ "type": "null" }),
+        (Tagging::Untagged, Some(content)) => content,
+    }
+}
+
+fn inner_schema(inner: TypeRef, defs: &mut Map<String, Value>) -> Value {
+    match inner {
+        TypeRef::Generated(schema) => {
+            let schema = schema();
+            let name = schema.name();
+            if !defs.contains_key(name) {
+                // Claim the name first, so that a type referring back to
+                // itself does not recurse forever.
+                defs.insert(name.to_string(), Value::Null);
+                let def = type_schema(schema, defs);
+                defs.insert(name.to_string(), def);
+            }
+            json!({ "$ref": format!("#/$defs/{}", name) })
+        }
+        TypeRef::Static(ty) => static_schema(ty),
+    }
+}
+
+fn wrap(schema: Value, wrapper: Wrapper) -> Value {
+    match wrapper {
+        Wrapper::Plain => schema,
+        Wrapper::Vec => json!({ "type": "array", "items": schema }),
+        Wrapper::Option => nullable(schema),
+        Wrapper::OptionVec => nullable(json!({ "type": "array", "items": schema })),
+    }
+}
+
+fn nullable(schema: Value) -> Value {
+    json!({ "anyOf": [schema, { "type": "null" }] })
+}
+
+/// The schema of a type written in the DSL, as far as it can be told from its
+/// source text. Unknown types accept anything.
+fn static_schema(ty: &str) -> Value {
+    let ty = ty.trim();
+    if let Some(ty) = ty.strip_prefix('&') {
+        let ty = ty.strip_prefix("'static").unwrap_or(ty);
+        return static_schema(ty);
+    }
+    if ty == "()" {
+        return json!({ "type": "null" });
+    }
+    if let Some(item) = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+        let item = item.split(';').next().unwrap_or(item);
+        return json!({ "type": "array", "items": static_schema(item) });
+    }
+
+    let (name, args) = split_type(ty);
+    match (name, args.as_slice()) {
+        ("String" | "str" | "char" | "PathBuf" | "Path", _) => json!({ "type": "string" }),
+        ("bool", _) => json!({ "type": "boolean" }),
+        ("u8" | "u16" | "u32" | "u64" | "u128" | "usize", _) => {
+            json!({ "type": "integer", "minimum": 0 })
+        }
+        ("i8" | "i16" | "i32" | "i64" | "i128" | "isize", _) => json!({ "type": "integer" }),
+        ("f32" | "f64", _) => json!({ "type": "number" }),
+        ("Option", [item]) => nullable(static_schema(item)),
+        ("Vec" | "VecDeque" | "LinkedList", [item]) => {
+            json!({ "type": "array", "items": static_schema(item) })
+        }
+        ("HashSet" | "BTreeSet", [item]) => {
+            json!({ "type": "array", "items": static_schema(item), "uniqueItems": true })
+        }
+        ("HashMap" | "BTreeMap", [_, value]) => {
+            json!({ "type": "object", "additionalProperties": static_schema(value) })
+        }
+        ("Box" | "Rc" | "Arc" | "Cow", [.., item]) => static_schema(item),
+        _ => json!({}),
+    }
+}
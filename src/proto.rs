@@ -0,0 +1,309 @@
+//! proto3 definitions mirroring the types generated with `#[yuuka(schema)]`.
+
+use std::collections::HashSet;
+
+use crate::schema::{split_type, FieldSchema, Schema, TypeRef, TypeSchema, VariantShape, Wrapper};
+
+/// A proto3 file with `T` and every generated type nested in it.
+pub fn proto<T: Schema>(package: Option<&str>) -> String {
+    T::schema().proto(package)
+}
+
+impl TypeSchema {
+    /// A proto3 file with this type and every generated type nested in it.
+    ///
+    /// Structs become messages, with the types defined inside them nested in
+    /// the same way. Enums with only unit variants become proto enums, other
+    /// enums become messages holding a `oneof`.
+    pub fn proto(&'static self, package: Option<&str>) -> String {
+        let mut writer = Writer::default();
+        writer.line("syntax = \"proto3\";");
+        writer.line("");
+        if let Some(package) = package {
+            writer.line(&format!("package {};", package));
+            writer.line("");
+        }
+        type_def(&mut writer, self, &proto_name(self.name()));
+        writer.text
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    text: String,
+    depth: usize,
+}
+
+impl Writer {
+    fn line(&mut self, line: &str) {
+        if !line.is_empty() {
+            for _ in 0..self.depth {
+                self.text.push_str("  ");
+            }
+        }
+        self.text.push_str(line);
+        self.text.push('\n');
+    }
+
+    fn open(&mut self, line: &str) {
+        self.line(line);
+        self.depth += 1;
+    }
+
+    fn close(&mut self) {
+        self.depth -= 1;
+        self.line("}");
+    }
+}
+
+fn type_def(writer: &mut Writer, schema: &'static TypeSchema, name: &str) {
+    match schema {
+        TypeSchema::Struct(v) => message(writer, name, v.fields, field_name),
+        TypeSchema::Enum(v)
+            if v.variants
+                .iter()
+                .all(|variant| matches!(variant.shape, VariantShape::Unit)) =>
+        {
+            writer.open(&format!("enum {} {{", name));
+            for variant in v.variants {
+                writer.line(&format!(
+                    "{}_{} = {};",
+                    screaming_snake_case(name),
+                    screaming_snake_case(variant.name),
+                    variant.proto_number
+                ));
+            }
+            writer.close();
+        }
+        TypeSchema::Enum(v) => {
+            writer.open(&format!("message {} {{", name));
+
+            // Every variant is an alternative of the `oneof`. Variants that do
+            // not fit in a single field get a message of their own.
+            let mut alternatives = vec![];
+            for variant in v.variants {
+                let ty = match variant.shape {
+                    VariantShape::Tuple([item]) if field_type(item, "").0.is_empty() => {
+                        match item.inner {
+                            // An anonymous type is named after the variant.
+                            TypeRef::Generated(schema) => {
+                                let ty = type_name(schema(), variant.name);
+                                type_def(writer, schema(), &ty);
+                                ty
+                            }
+                            TypeRef::Static(_) => field_type(item, "").1,
+                        }
+                    }
+                    VariantShape::Unit => {
+                        writer.line(&format!("message {} {{}}", variant.name));
+                        variant.name.to_string()
+                    }
+                    VariantShape::Tuple(fields) => {
+                        message(writer, variant.name, fields, |index, _| match fields {
+                            [_] => "value".to_string(),
+                            _ => format!("field_{}", index),
+                        });
+                        variant.name.to_string()
+                    }
+                    VariantShape::Struct(fields) => {
+                        message(writer, variant.name, fields, field_name);
+                        variant.name.to_string()
+                    }
+                };
+                alternatives.push(format!(
+                    "{} {} = {};",
+                    ty,
+                    snake_case(variant.name),
+                    variant.proto_number
+                ));
+            }
+
+            writer.open("oneof value {");
+            for alternative in &alternatives {
+                writer.line(alternative);
+            }
+            writer.close();
+            writer.close();
+        }
+    }
+}
+
+/// A message with the given fields, named by `field_names`.
+fn message(
+    writer: &mut Writer,
+    name: &str,
+    fields: &'static [FieldSchema],
+    field_names: impl Fn(usize, &FieldSchema) -> String,
+) {
+    if fields.is_empty() {
+        writer.line(&format!("message {} {{}}", name));
+        return;
+    }
+
+    writer.open(&format!("message {} {{", name));
+    let names = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| field_names(index, field))
+        .collect::<Vec<_>>();
+    // An anonymous type named after its field must not take the name of a
+    // named type next to it.
+    let mut taken = fields
+        .iter()
+        .filter_map(|field| match field.inner {
+            TypeRef::Generated(schema) if !schema().anonymous() => {
+                Some(proto_name(schema().name()))
+            }
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    let types = fields
+        .iter()
+        .zip(&names)
+        .map(|(field, name)| match field.inner {
+            TypeRef::Generated(schema) if schema().anonymous() => {
+                let mut ty = type_name(schema(), name);
+                while !taken.insert(ty.clone()) {
+                    ty.push('_');
+                }
+                ty
+            }
+            TypeRef::Generated(schema) => type_name(schema(), name),
+            TypeRef::Static(_) => String::new(),
+        })
+        .collect::<Vec<_>>();
+
+    for (field, ty) in fields.iter().zip(&types) {
+        if let TypeRef::Generated(schema) = field.inner {
+            type_def(writer, schema(), ty);
+        }
+    }
+    for ((field, name), ty) in fields.iter().zip(&names).zip(&types) {
+        let (label, ty) = field_type(field, ty);
+        writer.line(&format!(
+            "{}{} {} = {};",
+            label, ty, name, field.proto_number
+        ));
+    }
+    writer.close();
+}
+
+fn field_name(_: usize, field: &FieldSchema) -> String {
+    field.name.trim_start_matches("r#").to_string()
+}
+
+/// The name of a generated type nested in a message. Anonymous types are
+/// named after the field holding them, so that their names do not change
+/// when other anonymous types are added or moved.
+fn type_name(schema: &TypeSchema, field_name: &str) -> String {
+    if schema.anonymous() {
+        upper_camel_case(field_name)
+    } else {
+        proto_name(schema.name())
+    }
+}
+
+/// The label, like `repeated `, and the type of a field, where `generated`
+/// is the name of a generated type.
+fn field_type(field: &FieldSchema, generated: &str) -> (&'static str, String) {
+    let (label, ty) = match field.inner {
+        TypeRef::Generated(_) => ("", generated.to_string()),
+        TypeRef::Static(ty) => static_type(ty),
+    };
+    let label = match field.wrapper {
+        Wrapper::Plain => label,
+        Wrapper::Option => optional(label, &ty),
+        Wrapper::Vec | Wrapper::OptionVec => "repeated ",
+    };
+    (label, ty)
+}
+
+/// The label of an optional field. Repeated fields and maps cannot be marked
+/// `optional`, since being empty already means they are missing.
+fn optional(label: &'static str, ty: &str) -> &'static str {
+    if label.is_empty() && !ty.starts_with("map<") {
+        "optional "
+    } else {
+        label
+    }
+}
+
+/// The proto type of a type written in the DSL, as far as it can be told from
+/// its source text. Unknown types are assumed to be messages of the same name.
+fn static_type(ty: &str) -> (&'static str, String) {
+    let ty = ty.trim();
+    if let Some(ty) = ty.strip_prefix('&') {
+        let ty = ty.strip_prefix("'static").unwrap_or(ty);
+        return static_type(ty);
+    }
+
+    let (name, args) = split_type(ty);
+    let ty = match (name, args.as_slice()) {
+        ("String" | "str" | "char" | "PathBuf" | "Path", _) => "string",
+        ("bool", _) => "bool",
+        ("u8" | "u16" | "u32", _) => "uint32",
+        ("u64" | "u128" | "usize", _) => "uint64",
+        ("i8" | "i16" | "i32", _) => "int32",
+        ("i64" | "i128" | "isize", _) => "int64",
+        ("f32", _) => "float",
+        ("f64", _) => "double",
+        ("Vec", ["u8"]) => "bytes",
+        ("Option", [item]) => {
+            let (label, ty) = static_type(item);
+            return (optional(label, &ty), ty);
+        }
+        ("Vec" | "VecDeque" | "LinkedList" | "HashSet" | "BTreeSet", [item]) => {
+            return ("repeated ", static_type(item).1);
+        }
+        ("HashMap" | "BTreeMap", [key, value]) => {
+            return (
+                "",
+                format!("map<{}, {}>", static_type(key).1, static_type(value).1),
+            );
+        }
+        ("Box" | "Rc" | "Arc" | "Cow", [.., item]) => return static_type(item),
+        _ => return ("", proto_name(name)),
+    };
+    ("", ty.to_string())
+}
+
+/// Proto identifiers start with a letter, which named types may not.
+fn proto_name(name: &str) -> String {
+    name.trim_start_matches('_').to_string()
+}
+
+fn upper_camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut ret = String::new();
+    for (index, ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() {
+            let is_word_start = index > 0
+                && (chars[index - 1].is_lowercase()
+                    || chars[index - 1].is_ascii_digit()
+                    || chars.get(index + 1).is_some_and(|ch| ch.is_lowercase()));
+            if is_word_start && !ret.ends_with('_') {
+                ret.push('_');
+            }
+            ret.extend(ch.to_lowercase());
+        } else {
+            ret.push(*ch);
+        }
+    }
+    ret
+}
+
+fn screaming_snake_case(name: &str) -> String {
+    snake_case(name.trim_start_matches('_')).to_uppercase()
+}
@@ -0,0 +1,199 @@
+//! Human-readable references of the types generated with `#[yuuka(schema)]`,
+//! listing every field with its type, default value and `///` comments.
+
+use crate::schema::{
+    FieldSchema, Schema, TypeRef, TypeSchema, VariantSchema, VariantShape, Wrapper,
+};
+
+/// The layout of a reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceFormat {
+    Markdown,
+    Text,
+}
+
+/// A reference of every field in `T` and the generated types nested in it.
+pub fn reference<T: Schema>(format: ReferenceFormat) -> String {
+    T::schema().reference(format)
+}
+
+impl TypeSchema {
+    /// A reference of every field in this type and the generated types nested
+    /// in it.
+    ///
+    /// Fields are listed by the path of their serde names. `[]` marks a list,
+    /// and the fields of an enum variant sit under the name of the variant,
+    /// like `services[].method.Proxy.host`.
+    pub fn reference(&'static self, format: ReferenceFormat) -> String {
+        let mut entries = vec![];
+        collect_entries(self, "", &mut entries);
+
+        let docs = match self {
+            TypeSchema::Struct(v) => v.docs,
+            TypeSchema::Enum(v) => v.docs,
+        };
+        let mut ret = String::new();
+        match format {
+            ReferenceFormat::Markdown => {
+                ret.push_str(&format!("# {}\n", self.name()));
+                if let Some(docs) = docs {
+                    ret.push_str(&format!("\n{}\n", docs));
+                }
+                for entry in &entries {
+                    ret.push_str(&format!("\n## `{}`\n\n", entry.path));
+                    if let Some(docs) = entry.field.docs {
+                        ret.push_str(&format!("{}\n\n", docs));
+                    }
+                    ret.push_str(&format!("- Type: `{}`\n", entry.field.ty));
+                    ret.push_str(&format!("- Optional: {}\n", yes_no(entry.optional())));
+                    if let Some(default) = entry.default {
+                        ret.push_str(&format!("- Default: `{}`\n", default));
+                    }
+                    if !entry.variants.is_empty() {
+                        ret.push_str("- Variants:\n");
+                        for variant in &entry.variants {
+                            ret.push_str(&format!("  - `{}`", variant.serde_name));
+                            if let Some(docs) = variant.docs {
+                                ret.push_str(&format!(": {}", docs.replace('\n', " ")));
+                            }
+                            ret.push('\n');
+                        }
+                    }
+                }
+            }
+            ReferenceFormat::Text => {
+                ret.push_str(&format!("{}\n", self.name()));
+                if let Some(docs) = docs {
+                    ret.push_str(&format!("{}\n", docs));
+                }
+                for entry in &entries {
+                    ret.push_str(&format!("\n{}\n", entry.path));
+                    if let Some(docs) = entry.field.docs {
+                        for line in docs.lines() {
+                            if !line.is_empty() {
+                                ret.push_str("    ");
+                            }
+                            ret.push_str(line);
+                            ret.push('\n');
+                        }
+                    }
+                    ret.push_str(&format!("    Type: {}\n", entry.field.ty));
+                    ret.push_str(&format!("    Optional: {}\n", yes_no(entry.optional())));
+                    if let Some(default) = entry.default {
+                        ret.push_str(&format!("    Default: {}\n", default));
+                    }
+                    if !entry.variants.is_empty() {
+                        ret.push_str("    Variants:\n");
+                        for variant in &entry.variants {
+                            ret.push_str(&format!("        {}", variant.serde_name));
+                            if let Some(docs) = variant.docs {
+                                ret.push_str(&format!(" - {}", docs.replace('\n', " ")));
+                            }
+                            ret.push('\n');
+                        }
+                    }
+                }
+            }
+        }
+        ret
+    }
+}
+
+struct Entry {
+    path: String,
+    field: &'static FieldSchema,
+    /// The variants of an enum field.
+    variants: Vec<&'static VariantSchema>,
+    /// The default value of the field, or else of its enum.
+    default: Option<&'static str>,
+}
+
+impl Entry {
+    fn optional(&self) -> bool {
+        matches!(self.field.wrapper, Wrapper::Option | Wrapper::OptionVec)
+            || self.field.ty.starts_with("Option<")
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn collect_entries(schema: &'static TypeSchema, path: &str, entries: &mut Vec<Entry>) {
+    match schema {
+        TypeSchema::Struct(v) => {
+            for field in v.fields.iter().filter(|field| !field.serde_skip) {
+                collect_field(field, &join(path, field.serde_name), entries);
+            }
+        }
+        TypeSchema::Enum(v) => {
+            for variant in v.variants.iter().filter(|variant| !variant.serde_skip) {
+                let variant_path = join(path, variant.serde_name);
+                match variant.shape {
+                    VariantShape::Unit => {}
+                    VariantShape::Tuple([item]) => collect_field(item, &variant_path, entries),
+                    VariantShape::Tuple(fields) => {
+                        for field in fields {
+                            collect_field(field, &join(&variant_path, field.name), entries);
+                        }
+                    }
+                    VariantShape::Struct(fields) => {
+                        for field in fields.iter().filter(|field| !field.serde_skip) {
+                            collect_field(field, &join(&variant_path, field.serde_name), entries);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn collect_field(field: &'static FieldSchema, path: &str, entries: &mut Vec<Entry>) {
+    let is_list = matches!(field.wrapper, Wrapper::Vec | Wrapper::OptionVec)
+        || field.ty.starts_with("Vec<")
+        || field.ty.starts_with("Option<Vec<");
+    let path = if is_list {
+        format!("{}[]", path)
+    } else {
+        path.to_string()
+    };
+
+    let nested = match field.inner {
+        TypeRef::Generated(schema) => Some(schema()),
+        TypeRef::Static(_) => None,
+    };
+    let (variants, enum_default) = match nested {
+        Some(TypeSchema::Enum(v)) => (
+            v.variants
+                .iter()
+                .filter(|variant| !variant.serde_skip)
+                .collect(),
+            // Written out as `Self::Variant` in the generated `Default`.
+            v.default
+                .map(|default| default.strip_prefix("Self::").unwrap_or(default)),
+        ),
+        _ => (vec![], None),
+    };
+    entries.push(Entry {
+        path: path.clone(),
+        field,
+        variants,
+        default: field.default.or(enum_default),
+    });
+
+    if let Some(schema) = nested {
+        collect_entries(schema, &path, entries);
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
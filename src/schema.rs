@@ -0,0 +1,243 @@
+//! Static descriptions of the types generated with `#[yuuka(schema)]`.
+
+/// Types that carry a static description of their shape.
+pub trait Schema {
+    fn schema() -> &'static TypeSchema;
+}
+
+/// A generated struct or enum.
+#[derive(Debug)]
+pub enum TypeSchema {
+    Struct(StructSchema),
+    Enum(EnumSchema),
+}
+
+#[derive(Debug)]
+pub struct StructSchema {
+    pub name: &'static str,
+    /// Whether the name was generated for an anonymous type.
+    pub anonymous: bool,
+    pub fields: &'static [FieldSchema],
+    /// Extra derive macros, like `Serialize`.
+    pub derives: &'static [&'static str],
+    /// Type-level attributes, without the `#[...]`.
+    pub attrs: &'static [&'static str],
+    /// Whether `#[serde(deny_unknown_fields)]` is set.
+    pub deny_unknown_fields: bool,
+    /// The `///` comments, one line per comment.
+    pub docs: Option<&'static str>,
+}
+
+#[derive(Debug)]
+pub struct EnumSchema {
+    pub name: &'static str,
+    /// Whether the name was generated for an anonymous type.
+    pub anonymous: bool,
+    pub variants: &'static [VariantSchema],
+    /// The default variant written in the DSL, like `Self::Direct`.
+    pub default: Option<&'static str>,
+    /// Extra derive macros, like `Serialize`.
+    pub derives: &'static [&'static str],
+    /// Type-level attributes, without the `#[...]`.
+    pub attrs: &'static [&'static str],
+    /// How serde tells the variants apart.
+    pub tagging: Tagging,
+    /// The `///` comments, one line per comment.
+    pub docs: Option<&'static str>,
+}
+
+/// The enum representations of serde.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tagging {
+    /// `{ "Variant": content }`, the default.
+    External,
+    /// `#[serde(tag = "...")]`, `{ "tag": "Variant", ...fields }`.
+    Internal { tag: &'static str },
+    /// `#[serde(tag = "...", content = "...")]`,
+    /// `{ "tag": "Variant", "content": content }`.
+    Adjacent {
+        tag: &'static str,
+        content: &'static str,
+    },
+    /// `#[serde(untagged)]`, only the content.
+    Untagged,
+}
+
+#[derive(Debug)]
+pub struct VariantSchema {
+    pub name: &'static str,
+    pub shape: VariantShape,
+    /// Variant-level attributes, without the `#[...]`.
+    pub attrs: &'static [&'static str],
+    /// The name serde reads and writes, after `rename` and `rename_all`.
+    pub serde_name: &'static str,
+    /// Whether serde skips the variant.
+    pub serde_skip: bool,
+    /// The protobuf number, from `#[yuuka(proto = 3)]` or the position of the
+    /// variant: the enum value starting from 0 when every variant is a unit,
+    /// and the `oneof` field number starting from 1 otherwise.
+    pub proto_number: u32,
+    /// The `///` comments, one line per comment.
+    pub docs: Option<&'static str>,
+}
+
+#[derive(Debug)]
+pub enum VariantShape {
+    Unit,
+    /// The items of a tuple variant, named `0`, `1`, ...
+    Tuple(&'static [FieldSchema]),
+    Struct(&'static [FieldSchema]),
+}
+
+#[derive(Debug)]
+pub struct FieldSchema {
+    pub name: &'static str,
+    /// The full type of the field, like `Option<Vec<String>>`.
+    pub ty: &'static str,
+    /// The type inside the wrapper.
+    pub inner: TypeRef,
+    pub wrapper: Wrapper,
+    /// The DSL default value, as source text.
+    pub default: Option<&'static str>,
+    /// Field-level attributes, without the `#[...]`.
+    pub attrs: &'static [&'static str],
+    /// The DSL default value as JSON, when it is a literal.
+    pub default_json: Option<&'static str>,
+    /// The name serde reads and writes, after `rename` and `rename_all`.
+    pub serde_name: &'static str,
+    /// Whether serde fills the field in when it is missing.
+    pub serde_default: bool,
+    /// Whether serde skips the field.
+    pub serde_skip: bool,
+    /// The protobuf field number, from `#[yuuka(proto = 3)]` or the position
+    /// of the field, starting from 1.
+    pub proto_number: u32,
+    /// The `///` comments, one line per comment.
+    pub docs: Option<&'static str>,
+}
+
+/// The type of a field, inside its wrapper.
+#[derive(Debug, Clone, Copy)]
+pub enum TypeRef {
+    /// A type written in the DSL, as source text.
+    Static(&'static str),
+    /// A struct or enum generated by the same macro call.
+    Generated(fn() -> &'static TypeSchema),
+}
+
+/// The wrapper the DSL put around a field type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wrapper {
+    Plain,
+    /// `[T]`, a `Vec<T>`.
+    Vec,
+    /// `?: T`, an `Option<T>`.
+    Option,
+    /// `?: [T]`, an `Option<Vec<T>>`.
+    OptionVec,
+}
+
+impl TypeSchema {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TypeSchema::Struct(v) => v.name,
+            TypeSchema::Enum(v) => v.name,
+        }
+    }
+
+    /// Whether the name was generated for an anonymous type.
+    pub fn anonymous(&self) -> bool {
+        match self {
+            TypeSchema::Struct(v) => v.anonymous,
+            TypeSchema::Enum(v) => v.anonymous,
+        }
+    }
+
+    /// Every field in this type and the generated types nested in it, with
+    /// its path, like `services.rules.method.Proxy.host`. Paths follow `ty!`,
+    /// so single-item tuple variants are named by the variant alone.
+    pub fn field_paths(&'static self) -> Vec<(String, &'static FieldSchema)> {
+        let mut paths = vec![];
+        self.collect_field_paths("", &mut paths);
+        paths
+    }
+
+    fn collect_field_paths(
+        &'static self,
+        path: &str,
+        paths: &mut Vec<(String, &'static FieldSchema)>,
+    ) {
+        match self {
+            TypeSchema::Struct(v) => collect_fields(v.fields, path, paths),
+            TypeSchema::Enum(v) => {
+                for variant in v.variants {
+                    let variant_path = join(path, variant.name);
+                    match &variant.shape {
+                        VariantShape::Unit => {}
+                        VariantShape::Tuple([field]) => {
+                            paths.push((variant_path.clone(), field));
+                            if let TypeRef::Generated(schema) = field.inner {
+                                schema().collect_field_paths(&variant_path, paths);
+                            }
+                        }
+                        VariantShape::Tuple(fields) | VariantShape::Struct(fields) => {
+                            collect_fields(fields, &variant_path, paths)
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn collect_fields(
+    fields: &'static [FieldSchema],
+    path: &str,
+    paths: &mut Vec<(String, &'static FieldSchema)>,
+) {
+    for field in fields {
+        let field_path = join(path, field.name);
+        paths.push((field_path.clone(), field));
+        if let TypeRef::Generated(schema) = field.inner {
+            schema().collect_field_paths(&field_path, paths);
+        }
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Split the source text of a type into the last segment of its path and its
+/// generic arguments, like `HashMap` and `["String", "Vec<u8>"]`.
+pub(crate) fn split_type(ty: &str) -> (&str, Vec<&str>) {
+    let (path, args) = match ty.find('<') {
+        Some(start) if ty.ends_with('>') => (&ty[..start], &ty[start + 1..ty.len() - 1]),
+        _ => (ty, ""),
+    };
+
+    let mut list = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, ch) in args.char_indices() {
+        match ch {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                list.push(args[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if !args[start..].trim().is_empty() {
+        list.push(args[start..].trim());
+    }
+
+    let path = path.trim();
+    (path.rsplit("::").next().unwrap_or(path), list)
+}
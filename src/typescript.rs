@@ -0,0 +1,229 @@
+//! TypeScript declarations for the JSON that the types generated with
+//! `#[yuuka(schema)]` serialize to.
+
+use std::fmt::Write;
+
+use crate::schema::{
+    split_type, FieldSchema, Schema, Tagging, TypeRef, TypeSchema, VariantSchema, VariantShape,
+    Wrapper,
+};
+
+/// TypeScript declarations of `T` and every generated type nested in it.
+pub fn typescript<T: Schema>() -> String {
+    T::schema().typescript()
+}
+
+impl TypeSchema {
+    /// TypeScript declarations of this type and every generated type nested
+    /// in it, each exported under its Rust name.
+    pub fn typescript(&'static self) -> String {
+        let mut queue = vec![self];
+        let mut index = 0;
+        let mut ret = String::new();
+        while let Some(schema) = queue.get(index).copied() {
+            if index > 0 {
+                ret.push('\n');
+            }
+            ret.push_str(&declaration(schema, &mut queue));
+            index += 1;
+        }
+        ret
+    }
+}
+
+fn declaration(schema: &'static TypeSchema, queue: &mut Vec<&'static TypeSchema>) -> String {
+    let mut ret = String::new();
+    match schema {
+        TypeSchema::Struct(v) => {
+            writeln!(ret, "export interface {} {{", v.name).unwrap();
+            for field in v.fields.iter().filter(|field| !field.serde_skip) {
+                writeln!(ret, "  {};", property(field, queue)).unwrap();
+            }
+            ret.push_str("}\n");
+        }
+        TypeSchema::Enum(v) => {
+            let variants = v
+                .variants
+                .iter()
+                .filter(|variant| !variant.serde_skip)
+                .map(|variant| variant_type(variant, v.tagging, queue))
+                .collect::<Vec<_>>();
+            if variants.is_empty() {
+                writeln!(ret, "export type {} = never;", v.name).unwrap();
+            } else {
+                write!(ret, "export type {} =", v.name).unwrap();
+                for variant in &variants {
+                    write!(ret, "\n  | {}", variant).unwrap();
+                }
+                ret.push_str(";\n");
+            }
+        }
+    }
+    ret
+}
+
+/// `name: type`, or `name?: type | null` for `Option` fields.
+fn property(field: &'static FieldSchema, queue: &mut Vec<&'static TypeSchema>) -> String {
+    let name = property_name(field.serde_name);
+    let ty = inner_type(field.inner, queue);
+    match field.wrapper {
+        Wrapper::Plain => {
+            if let Some(item) = field
+                .ty
+                .strip_prefix("Option<")
+                .and_then(|ty| ty.strip_suffix('>'))
+            {
+                format!("{}?: {} | null", name, static_type(item))
+            } else {
+                format!("{}: {}", name, ty)
+            }
+        }
+        Wrapper::Vec => format!("{}: {}", name, array(&ty)),
+        Wrapper::Option => format!("{}?: {} | null", name, ty),
+        Wrapper::OptionVec => format!("{}?: {} | null", name, array(&ty)),
+    }
+}
+
+fn property_name(name: &str) -> String {
+    let is_ident = name
+        .chars()
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_' || ch == '$')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '$');
+    if is_ident {
+        name.to_string()
+    } else {
+        format!("{:?}", name)
+    }
+}
+
+fn field_type(field: &'static FieldSchema, queue: &mut Vec<&'static TypeSchema>) -> String {
+    let ty = inner_type(field.inner, queue);
+    match field.wrapper {
+        Wrapper::Plain => ty,
+        Wrapper::Vec => array(&ty),
+        Wrapper::Option => format!("{} | null", ty),
+        Wrapper::OptionVec => format!("{} | null", array(&ty)),
+    }
+}
+
+fn object(fields: &'static [FieldSchema], queue: &mut Vec<&'static TypeSchema>) -> String {
+    let properties = fields
+        .iter()
+        .filter(|field| !field.serde_skip)
+        .map(|field| property(field, queue))
+        .collect::<Vec<_>>();
+    if properties.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{ {} }}", properties.join("; "))
+    }
+}
+
+fn variant_type(
+    variant: &'static VariantSchema,
+    tagging: Tagging,
+    queue: &mut Vec<&'static TypeSchema>,
+) -> String {
+    let name = format!("{:?}", variant.serde_name);
+    let content = match &variant.shape {
+        VariantShape::Unit => None,
+        VariantShape::Tuple([item]) => Some(field_type(item, queue)),
+        VariantShape::Tuple(items) => {
+            let items = items
+                .iter()
+                .map(|item| field_type(item, queue))
+                .collect::<Vec<_>>();
+            Some(format!("[{}]", items.join(", ")))
+        }
+        VariantShape::Struct(fields) => Some(object(fields, queue)),
+    };
+
+    match (tagging, content) {
+        (Tagging::External, None) => name,
+        (Tagging::External, Some(content)) => {
+            format!("{{ {}: {} }}", property_name(variant.serde_name), content)
+        }
+        (Tagging::Internal { tag }, None) => format!("{{ {}: {} }}", property_name(tag), name),
+        (Tagging::Internal { tag }, Some(content)) => {
+            let tag = format!("{}: {}", property_name(tag), name);
+            match content.strip_prefix("{ ") {
+                // The fields of a struct variant sit next to the tag.
+                Some(fields) if matches!(variant.shape, VariantShape::Struct(_)) => {
+                    format!("{{ {}; {}", tag, fields)
+                }
+                _ if matches!(variant.shape, VariantShape::Struct(_)) => format!("{{ {} }}", tag),
+                _ => format!("{{ {} }} & {}", tag, content),
+            }
+        }
+        (Tagging::Adjacent { tag, .. }, None) => format!("{{ {}: {} }}", property_name(tag), name),
+        (Tagging::Adjacent { tag, content: key }, Some(content)) => format!(
+            "{{ {}: {}; {}: {} }}",
+            property_name(tag),
+            name,
+            property_name(key),
+            content
+        ),
+        (Tagging::Untagged, None) => "null".to_string(),
+        (Tagging::Untagged, Some(content)) => content,
+    }
+}
+
+fn inner_type(inner: TypeRef, queue: &mut Vec<&'static TypeSchema>) -> String {
+    match inner {
+        TypeRef::Generated(schema) => {
+            let schema = schema();
+            if !queue.iter().any(|v| std::ptr::eq(*v, schema)) {
+                queue.push(schema);
+            }
+            schema.name().to_string()
+        }
+        TypeRef::Static(ty) => static_type(ty),
+    }
+}
+
+/// `T[]`, with parentheses around unions.
+fn array(item: &str) -> String {
+    if item.contains(" | ") || item.contains(" & ") {
+        format!("({})[]", item)
+    } else {
+        format!("{}[]", item)
+    }
+}
+
+/// The TypeScript type of a type written in the DSL, as far as it can be told
+/// from its source text. Unknown types become `unknown`.
+fn static_type(ty: &str) -> String {
+    let ty = ty.trim();
+    if let Some(ty) = ty.strip_prefix('&') {
+        let ty = ty.strip_prefix("'static").unwrap_or(ty);
+        return static_type(ty);
+    }
+    if ty == "()" {
+        return "null".to_string();
+    }
+    if let Some(item) = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+        let item = item.split(';').next().unwrap_or(item);
+        return array(&static_type(item));
+    }
+
+    let (name, args) = split_type(ty);
+    match (name, args.as_slice()) {
+        ("String" | "str" | "char" | "PathBuf" | "Path", _) => "string".to_string(),
+        ("bool", _) => "boolean".to_string(),
+        (
+            "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64" | "i128"
+            | "isize" | "f32" | "f64",
+            _,
+        ) => "number".to_string(),
+        ("Option", [item]) => format!("{} | null", static_type(item)),
+        ("Vec" | "VecDeque" | "LinkedList" | "HashSet" | "BTreeSet", [item]) => {
+            array(&static_type(item))
+        }
+        ("HashMap" | "BTreeMap", [_, value]) => format!("Record<string, {}>", static_type(value)),
+        ("Box" | "Rc" | "Arc" | "Cow", [.., item]) => static_type(item),
+        _ => "unknown".to_string(),
+    }
+}
@@ -1,3 +0,0 @@
-mod flatten;
-
-pub(crate) use flatten::flatten;
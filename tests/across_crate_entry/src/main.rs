@@ -1,4 +1,4 @@
-use yuuka::auto;
+use yuuka::{auto, ty};
 
 use across_crate_lib::*;
 
@@ -23,6 +23,9 @@ fn main() {
         }
     );
 
+    let test_struct_c: ty!(TestStruct.c) = test_struct.c.clone();
+    assert_eq!(test_struct_c.d, 2);
+
     let test_enum = auto!(TestEnum::C::F::H("Hello".to_string()));
     assert_eq!(test_enum, TestEnum::C(C::F(F::H("Hello".to_string()))));
 }
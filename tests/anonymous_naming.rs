@@ -0,0 +1,120 @@
+use yuuka::{derive_enum, derive_struct};
+
+#[test]
+fn anonymous_naming_path() {
+    derive_struct!(
+        #[derive(PartialEq)]
+        #[anonymous_naming(path)]
+        Root {
+            nick_name: {
+                chinese: {
+                    first_name: String = "早濑".to_string(),
+                },
+            },
+            members: [{
+                name: String,
+            }],
+            status?: enum {
+                Active,
+                Inactive,
+            },
+        }
+    );
+
+    let root = Root::default();
+    assert_eq!(
+        root.nick_name,
+        Root_NickName {
+            chinese: Root_NickName_Chinese {
+                first_name: "早濑".to_string(),
+            },
+        }
+    );
+    let _ = Root_Members::default();
+    let _ = Root_Status::Active;
+}
+
+#[test]
+fn anonymous_naming_path_camel() {
+    derive_struct!(
+        #[anonymous_naming(path_camel)]
+        Root {
+            nick_name: {
+                chinese: {
+                    first_name: String,
+                },
+            },
+        }
+    );
+
+    let _ = RootNickName {
+        chinese: RootNickNameChinese {
+            first_name: "早濑".to_string(),
+        },
+    };
+}
+
+#[test]
+fn anonymous_naming_path_is_stable() {
+    derive_struct!(
+        #[anonymous_naming(path)]
+        Root {
+            inserted: {
+                value: i32,
+            },
+            nick_name: {
+                chinese: {
+                    first_name: String,
+                },
+            },
+        }
+    );
+
+    let _ = Root_NickName_Chinese {
+        first_name: "早濑".to_string(),
+    };
+}
+
+#[test]
+fn anonymous_naming_path_from_named_parent() {
+    derive_struct!(
+        #[anonymous_naming(path)]
+        Root {
+            location: Location {
+                address: {
+                    street: String,
+                },
+            },
+        }
+    );
+
+    let _ = Location_Address {
+        street: "777".to_string(),
+    };
+}
+
+#[test]
+fn anonymous_naming_path_enum() {
+    derive_enum!(
+        #[derive(PartialEq)]
+        #[anonymous_naming(path)]
+        enum Group {
+            Millennium(enum {
+                GameDevelopment,
+                Seminar {
+                    members: [{
+                        name: String,
+                    }],
+                },
+            }),
+            Gehenna(String, enum {
+                Hina,
+                Ako,
+            }),
+        }
+    );
+
+    let _ = Group::Millennium(Group_Millennium::GameDevelopment);
+    let _ = Group_Millennium_Seminar_Members::default();
+    let _ = Group::Gehenna("Prefect".to_string(), Group_Gehenna_1::Hina);
+}
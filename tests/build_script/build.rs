@@ -0,0 +1,16 @@
+fn main() {
+    yuuka_core::build::Codegen::new()
+        .derive_struct_file("schemas/config.yuuka")
+        .derive_enum(
+            r#"
+            #[derive(PartialEq, Serialize, Deserialize)]
+            pub enum Command {
+                Quit,
+                Move { x: i32, y: i32 },
+                Say(String),
+            } = Quit
+            "#,
+        )
+        .write("models.rs")
+        .unwrap();
+}
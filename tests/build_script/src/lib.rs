@@ -0,0 +1,5 @@
+//! Types written by `build.rs` with `yuuka_core::build`.
+
+use serde::{Deserialize, Serialize};
+
+include!(concat!(env!("OUT_DIR"), "/models.rs"));
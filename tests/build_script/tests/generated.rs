@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use yuuka::auto;
+
+include!(concat!(env!("OUT_DIR"), "/models.rs"));
+
+mod from_macro {
+    use serde::{Deserialize, Serialize};
+
+    yuuka::derive_struct_file!("schemas/config.yuuka");
+}
+
+#[test]
+fn generated_types() {
+    let config = auto!(GameConfig {
+        title: "Yuuka".to_string(),
+        window: {
+            width: 1920,
+            height: 1080,
+            full_screen: true,
+        },
+        plugins: vec![Plugin {
+            name: "audio".to_string(),
+            enabled: false,
+        }],
+        difficulty: Difficulty::Hard,
+    });
+    assert_eq!(config.window.width, 1920);
+    assert_eq!(Command::default(), Command::Quit);
+    assert_eq!(
+        auto!(Command::Move { x: 1, y: 2 }),
+        Command::Move { x: 1, y: 2 }
+    );
+
+    let source = include_str!(concat!(env!("OUT_DIR"), "/models.rs"));
+    assert!(source.contains("/// The title of the window."));
+}
+
+#[test]
+fn same_as_macro() {
+    let generated = serde_json::to_value(GameConfig::default()).unwrap();
+    let expanded = serde_json::to_value(from_macro::GameConfig::default()).unwrap();
+    assert_eq!(generated, expanded);
+
+    let value = serde_json::json!({
+        "title": "Yuuka",
+        "window": { "width": 1, "height": 2, "fullScreen": true },
+        "plugins": [{ "name": "audio", "enabled": true }],
+        "difficulty": "hard",
+    });
+    let generated = serde_json::from_value::<GameConfig>(value.clone()).unwrap();
+    let expanded = serde_json::from_value::<from_macro::GameConfig>(value.clone()).unwrap();
+    assert_eq!(serde_json::to_value(generated).unwrap(), value);
+    assert_eq!(serde_json::to_value(expanded).unwrap(), value);
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use yuuka::{auto, derive_enum_file, derive_struct_file};
+
+derive_struct_file!("tests/fixtures/config.yuuka");
+derive_enum_file!("tests/fixtures/command.yuuka");
+
+#[test]
+fn struct_from_file() {
+    let config = GameConfig::default();
+    assert_eq!(config.title, "Untitled");
+    assert_eq!(config.window.width, 1280);
+    assert_eq!(config.difficulty, Difficulty::Normal);
+
+    let config = auto!(GameConfig {
+        title: "Yuuka".to_string(),
+        window: {
+            width: 1920,
+            height: 1080,
+            full_screen: true,
+        },
+        plugins: vec![Plugin {
+            name: "audio".to_string(),
+            enabled: false,
+        }],
+        difficulty: Difficulty::Hard,
+    });
+    assert_eq!(
+        serde_json::to_value(&config).unwrap(),
+        serde_json::json!({
+            "title": "Yuuka",
+            "window": { "width": 1920, "height": 1080, "fullScreen": true },
+            "plugins": [{ "name": "audio", "enabled": false }],
+            "difficulty": "hard",
+        })
+    );
+}
+
+#[test]
+fn enum_from_file() {
+    assert_eq!(Command::default(), Command::Quit);
+    assert_eq!(
+        auto!(Command::Move { x: 1, y: 2 }),
+        Command::Move { x: 1, y: 2 }
+    );
+    assert_eq!(
+        serde_json::to_value(Command::Say("hi".to_string())).unwrap(),
+        serde_json::json!({ "Say": "hi" })
+    );
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use yuuka::{auto, derive_struct_from_json};
+
+derive_struct_from_json!(
+    #[derive(PartialEq, Serialize, Deserialize)]
+    Webhook,
+    "tests/fixtures/webhook.json"
+);
+
+const SAMPLE: &str = include_str!("fixtures/webhook.json");
+
+#[test]
+fn infer_fields() {
+    let webhook = serde_json::from_str::<Webhook>(SAMPLE).unwrap();
+
+    assert_eq!(webhook.action, "opened");
+    assert_eq!(webhook.number, 42i64);
+    assert_eq!(webhook.score, 1.5f64);
+    assert!(!webhook.draft);
+    assert_eq!(webhook.closed_at, None::<serde_json::Value>);
+    assert_eq!(webhook.r#type, "issue");
+    assert_eq!(
+        webhook.labels,
+        vec!["bug".to_string(), "help wanted".to_string()]
+    );
+    assert_eq!(
+        webhook.extra,
+        serde_json::json!([1, "two"]).as_array().cloned().unwrap()
+    );
+    assert!(webhook.empty.is_empty());
+}
+
+#[test]
+fn infer_nullable_items() {
+    let webhook = serde_json::from_str::<Webhook>(SAMPLE).unwrap();
+
+    // Items that are sometimes null are optional, and arrays of objects that
+    // hold null fall back to JSON values.
+    assert_eq!(webhook.ratings, vec![Some(5i64), None, Some(3)]);
+    assert_eq!(
+        webhook.reviewers,
+        vec![Some(serde_json::json!({ "login": "hubot" })), None]
+    );
+}
+
+#[test]
+fn infer_nested() {
+    let webhook = serde_json::from_str::<Webhook>(SAMPLE).unwrap();
+
+    assert_eq!(
+        webhook.repository,
+        Repository {
+            id: 1296269,
+            full_name: "octocat/hello-world".to_string(),
+            owner: Owner {
+                login: "octocat".to_string(),
+                site_admin: false,
+            },
+        }
+    );
+
+    // Keys that are null or missing in some items are optional, and numbers
+    // that are not always integers are floats.
+    assert_eq!(
+        webhook.commits[1],
+        Commit {
+            sha: "7dcb09c".to_string(),
+            message: None,
+            added: vec![],
+            distinct: None,
+            weight: Some(2.),
+        }
+    );
+    assert_eq!(webhook.commits[0].distinct, Some(true));
+}
+
+#[test]
+fn infer_round_trip() {
+    let webhook = serde_json::from_str::<Webhook>(SAMPLE).unwrap();
+    let value = serde_json::to_value(&webhook).unwrap();
+
+    assert_eq!(value["closedAt"], serde_json::Value::Null);
+    assert_eq!(value["repository"]["fullName"], "octocat/hello-world");
+}
+
+#[test]
+fn infer_auto() {
+    let owner = auto!(Owner {
+        login: "octocat".to_string(),
+        site_admin: true,
+    });
+    assert!(owner.site_admin);
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use yuuka::derive_struct_from_json_schema;
+
+derive_struct_from_json_schema!(
+    #[derive(PartialEq, Serialize, Deserialize)]
+    Config,
+    "tests/fixtures/config.schema.json"
+);
+
+#[test]
+fn import_fields() {
+    let config = serde_json::from_value::<Config>(json!({
+        "name": "api",
+        "server": { "host": "example.com", "port": 8080 },
+        "mode": "dev",
+        "tags": ["a", "b"],
+        "labels": { "team": "core" },
+        "metadata": { "owner": ["a"] },
+    }))
+    .unwrap();
+
+    assert_eq!(config.name, "api");
+    assert_eq!(config.retries, None);
+    assert_eq!(config.tags, Some(vec!["a".to_string(), "b".to_string()]));
+    assert_eq!(
+        config.server,
+        Server {
+            host: "example.com".to_string(),
+            port: 8080,
+            tls: None,
+        }
+    );
+    assert_eq!(config.mode, Mode::Dev);
+    assert_eq!(config.labels.unwrap()["team"], "core");
+    // Untyped properties are `yuuka::serde_json::Value`.
+    let metadata: Option<yuuka::serde_json::Value> = config.metadata;
+    assert_eq!(metadata, Some(json!({ "owner": ["a"] })));
+}
+
+#[test]
+fn import_defaults() {
+    let config = Config::default();
+
+    assert_eq!(config.retries, Some(3u64));
+    assert_eq!(config.ratio, Some(0.5));
+    assert_eq!(config.server.host, "localhost");
+    assert_eq!(config.mode, Mode::Prod);
+    assert_eq!(config.primary, None);
+}
+
+#[test]
+fn import_refs() {
+    let config = serde_json::from_value::<Config>(json!({
+        "name": "api",
+        "server": { "host": "example.com", "port": 8080 },
+        "mode": "prod",
+        "primary": { "url": "https://a" },
+        "backups": [{ "url": "https://b", "weight": 2 }],
+        "tree": {
+            "value": "root",
+            "children": [{ "value": "leaf" }],
+        },
+    }))
+    .unwrap();
+
+    assert_eq!(
+        config.primary,
+        Some(Endpoint {
+            url: "https://a".to_string(),
+            weight: None,
+        })
+    );
+    assert_eq!(config.backups.unwrap()[0].weight, Some(2));
+    let tree = config.tree.unwrap();
+    assert_eq!(tree.value, "root");
+    assert_eq!(tree.children.unwrap()[0].value, "leaf");
+    assert_eq!(Endpoint::default().weight, Some(1));
+}
+
+#[test]
+fn import_arrays_of_objects() {
+    let config = serde_json::from_value::<Config>(json!({
+        "name": "api",
+        "server": { "host": "example.com", "port": 8080 },
+        "mode": "prod",
+        "plugins": [{ "name": "audio" }],
+    }))
+    .unwrap();
+
+    assert_eq!(
+        config.plugins,
+        Some(vec![Plugin {
+            name: "audio".to_string(),
+            enabled: None,
+        }])
+    );
+}
+
+#[test]
+fn import_unions() {
+    let config = serde_json::from_value::<Config>(json!({
+        "name": "api",
+        "server": { "host": "example.com", "port": 8080 },
+        "mode": "prod",
+        "auth": { "Token": "secret" },
+        "storage": { "kind": "s3", "bucket": "logs" },
+        "limit": "none",
+    }))
+    .unwrap();
+
+    assert_eq!(config.auth, Some(Auth::Token("secret".to_string())));
+    assert_eq!(
+        config.storage,
+        Some(Storage::S3 {
+            bucket: "logs".to_string(),
+        })
+    );
+    assert_eq!(config.limit, Some(Limit::String("none".to_string())));
+
+    let value = serde_json::to_value(&config).unwrap();
+    assert_eq!(value["auth"], json!({ "Token": "secret" }));
+    assert_eq!(value["storage"], json!({ "kind": "s3", "bucket": "logs" }));
+    assert_eq!(value["limit"], json!("none"));
+    assert_eq!(serde_json::to_value(Auth::None).unwrap(), json!("None"));
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use yuuka::{derive_struct, Change};
+
+derive_struct!(
+    #[derive(PartialEq)]
+    #[yuuka(diff)]
+    Config {
+        name: String,
+        port?: u16,
+        services: [Service {
+            name: String,
+            rules: [Rule {
+                path: String,
+                method: enum Method {
+                    Direct,
+                    Proxy { host: String, port: u16 },
+                    Redirect(String),
+                    Rewrite(String, u16),
+                } = Direct,
+            }],
+        }],
+        meta?: {
+            tags: Vec<String>,
+        },
+    }
+);
+
+fn sample() -> Config {
+    Config {
+        name: "yuuka".to_string(),
+        port: None,
+        services: vec![
+            Service {
+                name: "api".to_string(),
+                rules: vec![],
+            },
+            Service {
+                name: "web".to_string(),
+                rules: vec![Rule {
+                    path: "/".to_string(),
+                    method: Method::Proxy {
+                        host: "localhost".to_string(),
+                        port: 8080,
+                    },
+                }],
+            },
+        ],
+        meta: Some(Default::default()),
+    }
+}
+
+#[test]
+fn diff_nested_leaves() {
+    let before = sample();
+    assert!(before.diff(&before).is_empty());
+
+    let mut after = sample();
+    after.port = Some(80);
+    after.services[1].rules[0].method = Method::Proxy {
+        host: "127.0.0.1".to_string(),
+        port: 8080,
+    };
+    after.meta.as_mut().unwrap().tags.push("new".to_string());
+
+    assert_eq!(
+        before.diff(&after),
+        vec![
+            Change {
+                path: "port".to_string(),
+                before: Some("None".to_string()),
+                after: Some("Some(80)".to_string()),
+            },
+            Change {
+                path: "services[1].rules[0].method.Proxy.host".to_string(),
+                before: Some(r#""localhost""#.to_string()),
+                after: Some(r#""127.0.0.1""#.to_string()),
+            },
+            Change {
+                path: "meta.tags".to_string(),
+                before: Some("[]".to_string()),
+                after: Some(r#"["new"]"#.to_string()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn diff_vec_and_variants() {
+    let before = sample();
+
+    let mut after = sample();
+    after.services.remove(0);
+
+    let changes = before.diff(&after);
+    assert_eq!(
+        changes
+            .iter()
+            .map(|change| change.to_string())
+            .collect::<Vec<_>>(),
+        vec![
+            r#"services[0].name: "api" -> "web""#.to_string(),
+            r#"services[0].rules[0]: <absent> -> Rule { path: "/", method: Proxy { host: "localhost", port: 8080 } }"#.to_string(),
+            r#"services[1]: Service { name: "web", rules: [Rule { path: "/", method: Proxy { host: "localhost", port: 8080 } }] } -> <absent>"#.to_string(),
+        ]
+    );
+
+    let mut before = sample();
+    before.services[1].rules[0].method = Method::Rewrite("/v1".to_string(), 301);
+    let mut after = sample();
+    after.services[1].rules[0].method = Method::Rewrite("/v2".to_string(), 301);
+    assert_eq!(
+        before.diff(&after)[0].path,
+        "services[1].rules[0].method.Rewrite.0"
+    );
+
+    before.services[1].rules[0].method = Method::Redirect("/v1".to_string());
+    after.services[1].rules[0].method = Method::Redirect("/v2".to_string());
+    assert_eq!(
+        before.diff(&after)[0].path,
+        "services[1].rules[0].method.Redirect"
+    );
+
+    after.services[1].rules[0].method = Method::Direct;
+    assert_eq!(
+        before.diff(&after),
+        vec![Change {
+            path: "services[1].rules[0].method".to_string(),
+            before: Some(r#"Redirect("/v1")"#.to_string()),
+            after: Some("Direct".to_string()),
+        }]
+    );
+}
+
+#[test]
+fn diff_leaves_by_equality() {
+    // The `Debug` output of a `HashMap` follows its iteration order, which
+    // differs between equal maps.
+    derive_struct!(
+        #[derive(PartialEq)]
+        #[yuuka(diff)]
+        Counts {
+            map: HashMap<String, u32>,
+        }
+    );
+
+    let before = Counts {
+        map: (0..32).map(|index| (index.to_string(), index)).collect(),
+    };
+    let after = Counts {
+        map: (0..32)
+            .rev()
+            .map(|index| (index.to_string(), index))
+            .collect(),
+    };
+    assert!(before == after);
+    assert!(before.diff(&after).is_empty());
+
+    let mut after = after;
+    after.map.insert("0".to_string(), 1);
+    assert_eq!(before.diff(&after)[0].path, "map");
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use yuuka::{derive_enum, derive_struct};
+
+derive_struct!(
+    #[derive(PartialEq, Serialize, Deserialize)]
+    #[yuuka(example)]
+    Config {
+        port: u16 = 8080,
+        name?: String,
+        tags: Vec<String>,
+        services: [Service {
+            domain: String = "example.com".to_string(),
+            rules?: [Rule {
+                pattern: String,
+                method: enum Method {
+                    Redirect { url: String },
+                    Proxy { host: String },
+                } = Proxy { host: "localhost".to_string() },
+            }],
+        }],
+        mode: enum {
+            Direct(String),
+            Relay,
+        } = Relay,
+        aliases: Vec<String> = vec!["a".to_string(), "b".to_string()],
+    }
+);
+
+#[test]
+fn example_struct() {
+    let example = Config::example();
+    assert_eq!(example.port, 8080);
+    assert_eq!(example.name, Some(String::new()));
+    assert!(example.tags.is_empty());
+    assert_eq!(
+        example.services,
+        vec![Service {
+            domain: "example.com".to_string(),
+            rules: Some(vec![Rule {
+                pattern: String::new(),
+                method: Method::Proxy {
+                    host: "localhost".to_string(),
+                },
+            }]),
+        }]
+    );
+    assert_eq!(example.aliases, vec!["a".to_string(), "b".to_string()]);
+}
+
+#[test]
+fn example_json() {
+    assert_eq!(
+        Config::example_json(),
+        json!({
+            "port": 8080,
+            "name": "",
+            "tags": [],
+            "services": [{
+                "domain": "example.com",
+                "rules": [{
+                    "pattern": "",
+                    "method": { "Proxy": { "host": "localhost" } },
+                }],
+            }],
+            "mode": "Relay",
+            "aliases": ["a", "b"],
+        })
+    );
+}
+
+#[test]
+fn example_enum() {
+    derive_enum!(
+        #[derive(PartialEq)]
+        #[yuuka(example)]
+        enum Shape {
+            Point { x: f64, y: f64, label?: String },
+            Circle(f64),
+        }
+    );
+
+    assert_eq!(
+        Shape::example(),
+        Shape::Point {
+            x: 0.,
+            y: 0.,
+            label: Some(String::new()),
+        }
+    );
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use yuuka::{derive_struct, graphql};
+
+derive_struct!(
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    #[yuuka(schema)]
+    Config {
+        listen_port: u16,
+        name?: String,
+        extra: HashMap<String, String>,
+        services: [Service {
+            domain: Vec<String>,
+            method: enum Method {
+                Redirect { url: String },
+                Static(String),
+                Pair(u32, bool),
+                Disabled,
+            } = Disabled,
+        }],
+        level: enum Level {
+            Low,
+            High,
+        } = Low,
+    }
+);
+
+#[test]
+fn graphql_types() {
+    assert_eq!(
+        graphql::<Config>(false),
+        r#"scalar JSON
+
+type Config {
+  listenPort: Int!
+  name: String
+  extra: JSON!
+  services: [Service!]!
+  level: Level!
+}
+
+type Service {
+  domain: [String!]!
+  method: Method!
+}
+
+enum Level {
+  Low
+  High
+}
+
+union Method = MethodRedirect | MethodStatic | MethodPair | MethodDisabled
+
+type MethodRedirect {
+  url: String!
+}
+
+type MethodStatic {
+  value: String!
+}
+
+type MethodPair {
+  _0: Int!
+  _1: Boolean!
+}
+
+type MethodDisabled {
+  _: Boolean
+}
+"#
+    );
+}
+
+#[test]
+fn graphql_inputs() {
+    derive_struct!(
+        #[yuuka(schema)]
+        Root {
+            tags?: Vec<String>,
+            mode: enum Mode {
+                Off,
+                Limit { max: i32 },
+                Points(f64, f64),
+            } = Off,
+        }
+    );
+
+    assert_eq!(
+        graphql::<Root>(true),
+        r#"type Root {
+  tags: [String!]
+  mode: Mode!
+}
+
+input RootInput {
+  tags: [String!]
+  mode: ModeInput!
+}
+
+union Mode = ModeOff | ModeLimit | ModePoints
+
+type ModeOff {
+  _: Boolean
+}
+
+type ModeLimit {
+  max: Int!
+}
+
+type ModePoints {
+  _0: Float!
+  _1: Float!
+}
+
+input ModeInput @oneOf {
+  Off: Boolean
+  Limit: ModeLimitInput
+  Points: ModePointsInput
+}
+
+input ModeLimitInput {
+  max: Int!
+}
+
+input ModePointsInput {
+  _0: Float!
+  _1: Float!
+}
+"#
+    );
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use yuuka::{derive_enum, derive_struct, json_schema};
+
+derive_struct!(
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase", deny_unknown_fields)]
+    #[yuuka(schema)]
+    Config {
+        name: String = "yuuka".to_string(),
+        listen_port?: u16,
+        tags: Vec<String> = vec!["a".to_string()],
+        #[serde(default)]
+        weights: HashMap<String, f64>,
+        services: [Service {
+            host: String,
+        }],
+        mode: enum Mode {
+            Direct,
+            Proxy(String),
+            Redirect { to: String },
+        } = Direct,
+    }
+);
+
+#[test]
+fn json_schema_struct() {
+    assert_eq!(
+        json_schema::<Config>(),
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Config",
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "default": "yuuka" },
+                "listenPort": {
+                    "anyOf": [{ "type": "integer", "minimum": 0 }, { "type": "null" }]
+                },
+                "tags": { "type": "array", "items": { "type": "string" }, "default": ["a"] },
+                "weights": {
+                    "type": "object",
+                    "additionalProperties": { "type": "number" }
+                },
+                "services": { "type": "array", "items": { "$ref": "#/$defs/Service" } },
+                "mode": { "$ref": "#/$defs/Mode" },
+            },
+            "required": ["name", "tags", "services", "mode"],
+            "additionalProperties": false,
+            "$defs": {
+                "Service": {
+                    "type": "object",
+                    "properties": { "host": { "type": "string" } },
+                    "required": ["host"],
+                },
+                "Mode": {
+                    "oneOf": [
+                        { "const": "Direct" },
+                        {
+                            "type": "object",
+                            "properties": { "Proxy": { "type": "string" } },
+                            "required": ["Proxy"],
+                            "additionalProperties": false,
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "Redirect": {
+                                    "type": "object",
+                                    "properties": { "to": { "type": "string" } },
+                                    "required": ["to"],
+                                },
+                            },
+                            "required": ["Redirect"],
+                            "additionalProperties": false,
+                        },
+                    ],
+                },
+            },
+        })
+    );
+}
+
+#[test]
+fn json_schema_serde_default() {
+    derive_struct!(
+        #[derive(Serialize, Deserialize)]
+        #[yuuka(schema, serde_default)]
+        Root { a: u32 = 1, b: i64 }
+    );
+
+    let schema = json_schema::<Root>();
+    assert_eq!(
+        schema["properties"]["a"],
+        json!({ "type": "integer", "minimum": 0, "default": 1 })
+    );
+    assert_eq!(schema["required"], json!(["b"]));
+    assert!(schema.get("$defs").is_none());
+}
+
+#[test]
+fn json_schema_internally_tagged() {
+    derive_enum!(
+        #[derive(Serialize, Deserialize)]
+        #[serde(tag = "type", rename_all = "snake_case")]
+        #[yuuka(schema)]
+        enum Event {
+            Started,
+            Stopped { code: i32 },
+        }
+    );
+
+    assert_eq!(
+        json_schema::<Event>()["oneOf"],
+        json!([
+            {
+                "type": "object",
+                "properties": { "type": { "const": "started" } },
+                "required": ["type"],
+            },
+            {
+                "type": "object",
+                "properties": {
+                    "code": { "type": "integer" },
+                    "type": { "const": "stopped" },
+                },
+                "required": ["type", "code"],
+            },
+        ])
+    );
+}
+
+#[test]
+fn json_schema_adjacently_tagged() {
+    derive_enum!(
+        #[derive(Serialize, Deserialize)]
+        #[serde(tag = "t", content = "c")]
+        #[yuuka(schema)]
+        enum Message {
+            Ping,
+            Text(String),
+            Point(i32, i32),
+        }
+    );
+
+    assert_eq!(
+        json_schema::<Message>()["oneOf"],
+        json!([
+            {
+                "type": "object",
+                "properties": { "t": { "const": "Ping" } },
+                "required": ["t"],
+            },
+            {
+                "type": "object",
+                "properties": { "t": { "const": "Text" }, "c": { "type": "string" } },
+                "required": ["t", "c"],
+            },
+            {
+                "type": "object",
+                "properties": {
+                    "t": { "const": "Point" },
+                    "c": {
+                        "type": "array",
+                        "prefixItems": [{ "type": "integer" }, { "type": "integer" }],
+                        "minItems": 2,
+                        "maxItems": 2,
+                    },
+                },
+                "required": ["t", "c"],
+            },
+        ])
+    );
+}
+
+#[test]
+fn json_schema_untagged() {
+    derive_enum!(
+        #[derive(Serialize, Deserialize)]
+        #[serde(untagged)]
+        #[yuuka(schema)]
+        enum Value {
+            Flag(bool),
+            Number(f64),
+            #[serde(skip)]
+            Hidden,
+        }
+    );
+
+    assert_eq!(
+        json_schema::<Value>(),
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Value",
+            "anyOf": [{ "type": "boolean" }, { "type": "number" }],
+        })
+    );
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use yuuka::derive_struct;
+
+derive_struct!(
+    #[derive(PartialEq, Serialize, Deserialize)]
+    #[yuuka(partial, skip_none, anonymous_naming = path_camel)]
+    Config {
+        name: String = "yuuka".to_string(),
+        port: u16 = 8080,
+        tags: Vec<String>,
+        log_level?: String,
+        database: {
+            host: String = "localhost".to_string(),
+            pool: {
+                size: u32 = 4,
+                timeout: u32 = 30,
+            },
+        },
+        tls?: Tls {
+            cert: String,
+            key: String,
+        },
+        mode: enum Mode {
+            Debug,
+            Release,
+        } = Debug,
+    }
+);
+
+#[test]
+fn partial_merge() {
+    let mut config = Config::default();
+    config.merge(ConfigPartial {
+        port: Some(9090),
+        log_level: Some("info".to_string()),
+        database: Some(ConfigDatabasePartial {
+            pool: Some(ConfigDatabasePoolPartial {
+                size: Some(16),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        tls: Some(TlsPartial {
+            cert: Some("cert.pem".to_string()),
+            ..Default::default()
+        }),
+        mode: Some(Mode::Release),
+        ..Default::default()
+    });
+
+    assert_eq!(config.name, "yuuka");
+    assert_eq!(config.port, 9090);
+    assert_eq!(config.log_level, Some("info".to_string()));
+    assert_eq!(config.database.host, "localhost");
+    assert_eq!(config.database.pool.size, 16);
+    assert_eq!(config.database.pool.timeout, 30);
+    assert_eq!(
+        config.tls,
+        Some(Tls {
+            cert: "cert.pem".to_string(),
+            key: "".to_string(),
+        })
+    );
+    assert_eq!(config.mode, Mode::Release);
+
+    // Unset fields in the partial leave the config untouched.
+    let before = config.clone();
+    config.merge(ConfigPartial::default());
+    assert_eq!(config, before);
+}
+
+#[test]
+fn partial_overlay_layers() {
+    let file: ConfigPartial =
+        serde_json::from_str(r#"{"port":9090,"database":{"pool":{"size":8,"timeout":10}}}"#)
+            .unwrap();
+    let env: ConfigPartial =
+        serde_json::from_str(r#"{"name":"noa","database":{"pool":{"size":16}}}"#).unwrap();
+
+    let layered = ConfigPartial::overlay(file, env);
+    assert_eq!(
+        serde_json::to_string(&layered).unwrap(),
+        r#"{"name":"noa","port":9090,"database":{"pool":{"size":16,"timeout":10}}}"#
+    );
+
+    let mut config = Config::default();
+    config.merge(layered);
+    assert_eq!(config.name, "noa");
+    assert_eq!(config.port, 9090);
+    assert_eq!(config.database.host, "localhost");
+    assert_eq!(config.database.pool.size, 16);
+    assert_eq!(config.database.pool.timeout, 10);
+}
+
+fn default_retries() -> u32 {
+    3
+}
+
+#[test]
+fn partial_field_attrs() {
+    // Serde options that refer to the type of a field are left out of the
+    // partial, while renames are kept.
+    derive_struct!(
+        #[derive(PartialEq, Serialize, Deserialize)]
+        #[yuuka(
+            partial,
+            recursive_field(wrapper = vec, serde(skip_serializing_if = "Vec::is_empty"))
+        )]
+        Job {
+            #[serde(default = "default_retries", rename = "maxRetries")]
+            retries: u32,
+            steps: [Step {
+                /// The command to run.
+                #[serde(alias = "cmd")]
+                command: String,
+                env: Vec<String>,
+            }],
+        }
+    );
+
+    let partial: JobPartial = serde_json::from_str(r#"{"maxRetries":5}"#).unwrap();
+    assert_eq!(partial.retries, Some(5));
+    assert_eq!(partial.steps, None);
+
+    let step: StepPartial = serde_json::from_str(r#"{"cmd":"make"}"#).unwrap();
+    assert_eq!(step.command, Some("make".to_string()));
+
+    let job: Job = serde_json::from_str(r#"{"steps":[]}"#).unwrap();
+    assert_eq!(job.retries, 3);
+    assert_eq!(serde_json::to_string(&job).unwrap(), r#"{"maxRetries":3}"#);
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use yuuka::{derive_enum, derive_struct, proto};
+
+derive_struct!(
+    #[yuuka(schema)]
+    Config {
+        #[yuuka(proto = 1)]
+        port: u16,
+        #[yuuka(proto = 10)]
+        name?: String,
+        #[yuuka(proto = 3)]
+        labels: HashMap<String, i64>,
+        #[yuuka(proto = 4)]
+        services: [Service {
+            domain: Vec<String>,
+            payload: Vec<u8>,
+            method: enum Method {
+                Redirect { url: String },
+                Static(String),
+                Pair(u32, bool),
+                Disabled,
+            } = Disabled,
+        }],
+        #[yuuka(proto = 5)]
+        level: enum {
+            Low,
+            HighPriority,
+        } = Low,
+    }
+);
+
+#[test]
+fn proto_message() {
+    assert_eq!(
+        proto::<Config>(Some("config.v1")),
+        r#"syntax = "proto3";
+
+package config.v1;
+
+message Config {
+  message Service {
+    message Method {
+      message Redirect {
+        string url = 1;
+      }
+      message Pair {
+        uint32 field_0 = 1;
+        bool field_1 = 2;
+      }
+      message Disabled {}
+      oneof value {
+        Redirect redirect = 1;
+        string static = 2;
+        Pair pair = 3;
+        Disabled disabled = 4;
+      }
+    }
+    repeated string domain = 1;
+    bytes payload = 2;
+    Method method = 3;
+  }
+  enum Level {
+    LEVEL_LOW = 0;
+    LEVEL_HIGH_PRIORITY = 1;
+  }
+  uint32 port = 1;
+  optional string name = 10;
+  map<string, int64> labels = 3;
+  repeated Service services = 4;
+  Level level = 5;
+}
+"#
+    );
+}
+
+#[test]
+fn proto_enum_root() {
+    derive_enum!(
+        #[yuuka(schema)]
+        enum Status {
+            Active,
+            Stopped,
+        }
+    );
+
+    assert_eq!(
+        proto::<Status>(None),
+        r#"syntax = "proto3";
+
+enum Status {
+  STATUS_ACTIVE = 0;
+  STATUS_STOPPED = 1;
+}
+"#
+    );
+}
+
+#[test]
+fn proto_anonymous_names() {
+    // Anonymous types are named after their fields, or their variants, and
+    // one that takes the name of a named type next to it gets a `_`.
+    derive_struct!(
+        #[yuuka(schema)]
+        Root {
+            extra: { a: u8 },
+            server: { host: String },
+            servers: [Server { port: u16 }],
+            mode: enum {
+                Manual(enum { Fast, Slow }),
+                Auto,
+            } = Auto,
+        }
+    );
+
+    assert_eq!(
+        proto::<Root>(None),
+        r#"syntax = "proto3";
+
+message Root {
+  message Extra {
+    uint32 a = 1;
+  }
+  message Server_ {
+    string host = 1;
+  }
+  message Server {
+    uint32 port = 1;
+  }
+  message Mode {
+    enum Manual {
+      MANUAL_FAST = 0;
+      MANUAL_SLOW = 1;
+    }
+    message Auto {}
+    oneof value {
+      Manual manual = 1;
+      Auto auto = 2;
+    }
+  }
+  Extra extra = 1;
+  Server_ server = 2;
+  repeated Server servers = 3;
+  Mode mode = 4;
+}
+"#
+    );
+}
+
+#[test]
+fn proto_variant_numbers() {
+    // Explicit numbers keep the wire format when variants are inserted.
+    derive_struct!(
+        #[yuuka(schema)]
+        Root {
+            method: enum Method {
+                #[yuuka(proto = 2)]
+                Redirect { url: String },
+                #[yuuka(proto = 5)]
+                Static(String),
+                #[yuuka(proto = 1)]
+                Disabled,
+            } = Disabled,
+            level: enum Level {
+                #[yuuka(proto = 0)]
+                Low,
+                #[yuuka(proto = 2)]
+                High,
+            } = Low,
+        }
+    );
+
+    assert_eq!(
+        proto::<Root>(None),
+        r#"syntax = "proto3";
+
+message Root {
+  message Method {
+    message Redirect {
+      string url = 1;
+    }
+    message Disabled {}
+    oneof value {
+      Redirect redirect = 2;
+      string static = 5;
+      Disabled disabled = 1;
+    }
+  }
+  enum Level {
+    LEVEL_LOW = 0;
+    LEVEL_HIGH = 2;
+  }
+  Method method = 1;
+  Level level = 2;
+}
+"#
+    );
+}
+
+#[test]
+fn proto_optional_map() {
+    // Maps cannot be `optional` in proto3.
+    derive_struct!(
+        #[yuuka(schema)]
+        Root {
+            labels?: HashMap<String, i64>,
+            extra: Option<HashMap<String, String>>,
+            name: Option<String>,
+        }
+    );
+
+    assert_eq!(
+        proto::<Root>(None),
+        r#"syntax = "proto3";
+
+message Root {
+  map<string, int64> labels = 1;
+  map<string, string> extra = 2;
+  optional string name = 3;
+}
+"#
+    );
+}
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use yuuka::{derive_enum, derive_struct};
+
+#[test]
+fn recursive_field_attr_all() {
+    derive_struct!(
+        #[yuuka(derive(Serialize, Deserialize), recursive_field(serde(default)))]
+        Root {
+            name: String = "yuuka".to_string(),
+            info: {
+                age: u32 = 16,
+                club: String = "seminar".to_string(),
+            },
+        }
+    );
+
+    let ret: Root = serde_json::from_str(r#"{"info":{"age":17}}"#).unwrap();
+    assert_eq!(ret.name, "");
+    assert_eq!(ret.info.age, 17);
+    assert_eq!(ret.info.club, "");
+}
+
+#[test]
+fn recursive_field_attr_option_filter() {
+    derive_struct!(
+        #[yuuka(
+            derive(Serialize, Deserialize),
+            recursive_field(
+                wrapper = option,
+                serde(skip_serializing_if = "Option::is_none")
+            )
+        )]
+        Root {
+            name: String,
+            nick_name?: String,
+            info: {
+                age?: u32,
+                tags: Vec<String>,
+            },
+        }
+    );
+
+    let ret = Root {
+        name: "yuuka".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        serde_json::to_string(&ret).unwrap(),
+        r#"{"name":"yuuka","info":{"tags":[]}}"#
+    );
+}
+
+#[test]
+fn recursive_field_attr_vec_filter() {
+    derive_struct!(
+        #[yuuka(
+            derive(Serialize, Deserialize),
+            recursive_field(wrapper = vec, serde(skip_serializing_if = "Vec::is_empty"))
+        )]
+        Root {
+            name: String,
+            tags: Vec<String>,
+            members: [{
+                name: String,
+                roles: Vec<String>,
+            }],
+        }
+    );
+
+    let ret = Root {
+        name: "seminar".to_string(),
+        tags: vec![],
+        members: vec![_Root_0_anonymous {
+            name: "yuuka".to_string(),
+            roles: vec![],
+        }],
+    };
+    assert_eq!(
+        serde_json::to_string(&ret).unwrap(),
+        r#"{"name":"seminar","members":[{"name":"yuuka"}]}"#
+    );
+}
+
+#[test]
+fn recursive_field_attr_enum_struct_variant() {
+    derive_enum!(
+        #[yuuka(
+            derive(Serialize, Deserialize),
+            recursive_field(wrapper = option, serde(skip_serializing_if = "Option::is_none"))
+        )]
+        enum Member {
+            Student {
+                name: String,
+                club?: String,
+            },
+            Teacher,
+        } = Teacher
+    );
+
+    assert_eq!(
+        serde_json::to_string(&Member::Student {
+            name: "yuuka".to_string(),
+            club: None,
+        })
+        .unwrap(),
+        r#"{"Student":{"name":"yuuka"}}"#
+    );
+}
+
+#[test]
+fn recursive_field_attr_from_field() {
+    derive_struct!(
+        #[derive(Serialize, Deserialize)]
+        Root {
+            name?: String,
+            #[yuuka(recursive_field(
+                wrapper = option,
+                serde(skip_serializing_if = "Option::is_none")
+            ))]
+            info: {
+                age?: u32,
+                detail: {
+                    club?: String,
+                },
+            },
+        }
+    );
+
+    assert_eq!(
+        serde_json::to_string(&Root::default()).unwrap(),
+        r#"{"name":null,"info":{"detail":{}}}"#
+    );
+}
+
+#[test]
+fn recursive_attr_on_field_is_applied_once() {
+    derive_struct!(
+        #[derive(Serialize, Deserialize)]
+        Root {
+            #[derive(PartialEq)]
+            #[macros_recursive(serde(rename_all = "camelCase"))]
+            nick_name: {
+                first_name: String = "Hayase".to_string(),
+                detail: {
+                    last_name: String = "Yuuka".to_string(),
+                },
+            },
+        }
+    );
+
+    assert_eq!(
+        serde_json::to_string(&Root::default()).unwrap(),
+        r#"{"nick_name":{"firstName":"Hayase","detail":{"lastName":"Yuuka"}}}"#
+    );
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use yuuka::derive_struct;
+
+#[test]
+fn recursive_selector_kind() {
+    derive_struct!(
+        #[yuuka(
+            derive(Serialize, Deserialize),
+            recursive(kind = enum, serde(tag = "type")),
+            recursive(kind = struct, serde(deny_unknown_fields)),
+        )]
+        Root {
+            name: String,
+            mode: enum Mode {
+                Direct,
+                Proxy { host: String },
+            } = Direct,
+        }
+    );
+
+    let ret = Root {
+        name: "yuuka".to_string(),
+        mode: Mode::Proxy {
+            host: "localhost".to_string(),
+        },
+    };
+    assert_eq!(
+        serde_json::to_string(&ret).unwrap(),
+        r#"{"name":"yuuka","mode":{"type":"Proxy","host":"localhost"}}"#
+    );
+    assert!(
+        serde_json::from_str::<Root>(r#"{"name":"yuuka","mode":{"type":"Direct"},"extra":0}"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn recursive_selector_anonymous() {
+    derive_struct!(
+        #[yuuka(
+            derive(Serialize, Deserialize),
+            recursive(anonymous, serde(rename_all = "UPPERCASE"))
+        )]
+        Root {
+            nick_name: String = "yuuka".to_string(),
+            info: {
+                first_name: String = "Hayase".to_string(),
+            },
+            club: Club {
+                club_name: String = "seminar".to_string(),
+            },
+        }
+    );
+
+    assert_eq!(
+        serde_json::to_string(&Root::default()).unwrap(),
+        r#"{"nick_name":"yuuka","info":{"FIRST_NAME":"Hayase"},"club":{"club_name":"seminar"}}"#
+    );
+}
+
+#[test]
+fn recursive_selector_depth() {
+    derive_struct!(
+        #[yuuka(
+            derive(Serialize, Deserialize),
+            recursive(depth = 1, serde(rename_all = "camelCase"))
+        )]
+        Root {
+            nick_name: {
+                first_name: String = "Hayase".to_string(),
+                detail: {
+                    last_name: String = "Yuuka".to_string(),
+                },
+            },
+        }
+    );
+
+    assert_eq!(
+        serde_json::to_string(&Root::default()).unwrap(),
+        r#"{"nick_name":{"firstName":"Hayase","detail":{"last_name":"Yuuka"}}}"#
+    );
+}
+
+#[test]
+fn recursive_selector_name_glob() {
+    derive_struct!(
+        #[yuuka(
+            derive(Serialize, Deserialize),
+            recursive(name = "*Name", named, serde(rename_all = "kebab-case"))
+        )]
+        Root {
+            nick_name: NickName {
+                first_name: String = "Hayase".to_string(),
+            },
+            live_in: LiveIn {
+                country_name: String = "kivotos".to_string(),
+            },
+        }
+    );
+
+    assert_eq!(
+        serde_json::to_string(&Root::default()).unwrap(),
+        r#"{"nick_name":{"first-name":"Hayase"},"live_in":{"country_name":"kivotos"}}"#
+    );
+}
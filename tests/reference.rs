@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use yuuka::{derive_struct, reference, ReferenceFormat};
+
+derive_struct!(
+    /// The server configuration.
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    #[yuuka(schema)]
+    Config {
+        /// The port to listen on.
+        listen_port: u16 = 8080,
+        /// Extra names.
+        ///
+        /// Matched in order.
+        aliases?: Vec<String>,
+        services: [Service {
+            domain: String,
+            method: enum Method {
+                /// Send a redirect.
+                Redirect { url: String },
+                Proxy(String),
+            } = Proxy("localhost".to_string()),
+        }],
+    }
+);
+
+#[test]
+fn reference_markdown() {
+    assert_eq!(
+        reference::<Config>(ReferenceFormat::Markdown),
+        r#"# Config
+
+The server configuration.
+
+## `listenPort`
+
+The port to listen on.
+
+- Type: `u16`
+- Optional: no
+- Default: `8080`
+
+## `aliases[]`
+
+Extra names.
+
+Matched in order.
+
+- Type: `Option<Vec<String>>`
+- Optional: yes
+
+## `services[]`
+
+- Type: `Vec<Service>`
+- Optional: no
+
+## `services[].domain`
+
+- Type: `String`
+- Optional: no
+
+## `services[].method`
+
+- Type: `Method`
+- Optional: no
+- Default: `Proxy("localhost".to_string())`
+- Variants:
+  - `Redirect`: Send a redirect.
+  - `Proxy`
+
+## `services[].method.Redirect.url`
+
+- Type: `String`
+- Optional: no
+
+## `services[].method.Proxy`
+
+- Type: `String`
+- Optional: no
+"#
+    );
+}
+
+#[test]
+fn reference_text() {
+    assert_eq!(
+        reference::<Service>(ReferenceFormat::Text),
+        r#"Service
+
+domain
+    Type: String
+    Optional: no
+
+method
+    Type: Method
+    Optional: no
+    Default: Proxy("localhost".to_string())
+    Variants:
+        Redirect - Send a redirect.
+        Proxy
+
+method.Redirect.url
+    Type: String
+    Optional: no
+
+method.Proxy
+    Type: String
+    Optional: no
+"#
+    );
+}
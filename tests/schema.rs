@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use yuuka::{
+    derive_enum, derive_struct,
+    schema::{TypeRef, TypeSchema, VariantShape, Wrapper},
+    Schema,
+};
+
+derive_struct!(
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    #[yuuka(schema)]
+    Config {
+        name: String = "yuuka".to_string(),
+        #[serde(rename = "listen_port")]
+        port?: u16,
+        tags: Vec<String> = vec!["a, (b)".to_string()],
+        services: [Service {
+            host: String,
+            method: enum {
+                Direct,
+                Proxy { host: String },
+                Redirect(String),
+            } = Direct,
+        }],
+    }
+);
+
+#[test]
+fn schema_struct() {
+    let TypeSchema::Struct(schema) = Config::schema() else {
+        panic!("expected a struct schema");
+    };
+    assert_eq!(schema.name, "Config");
+    assert!(!schema.anonymous);
+    assert_eq!(schema.derives, &["Serialize", "Deserialize"]);
+    assert_eq!(schema.attrs, &[r#"serde(rename_all = "camelCase")"#]);
+
+    let names = schema
+        .fields
+        .iter()
+        .map(|field| field.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["name", "port", "tags", "services"]);
+
+    let name = &schema.fields[0];
+    assert_eq!(name.ty, "String");
+    assert_eq!(name.wrapper, Wrapper::Plain);
+    assert_eq!(name.default, Some(r#""yuuka".to_string()"#));
+
+    let port = &schema.fields[1];
+    assert_eq!(port.ty, "Option<u16>");
+    assert_eq!(port.wrapper, Wrapper::Option);
+    assert!(matches!(port.inner, TypeRef::Static("u16")));
+    assert_eq!(port.attrs, &[r#"serde(rename = "listen_port")"#]);
+
+    let tags = &schema.fields[2];
+    assert_eq!(tags.ty, "Vec<String>");
+    assert_eq!(tags.default, Some(r#"vec!["a, (b)".to_string()]"#));
+
+    let services = &schema.fields[3];
+    assert_eq!(services.wrapper, Wrapper::Vec);
+    let TypeRef::Generated(service) = services.inner else {
+        panic!("expected a generated type");
+    };
+    assert_eq!(service().name(), "Service");
+}
+
+#[test]
+fn schema_field_paths() {
+    let paths = Config::schema()
+        .field_paths()
+        .into_iter()
+        .map(|(path, field)| (path, field.ty))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        vec![
+            ("name".to_string(), "String"),
+            ("port".to_string(), "Option<u16>"),
+            ("tags".to_string(), "Vec<String>"),
+            ("services".to_string(), "Vec<Service>"),
+            ("services.host".to_string(), "String"),
+            ("services.method".to_string(), "_Config_1_anonymous"),
+            ("services.method.Proxy.host".to_string(), "String"),
+            ("services.method.Redirect".to_string(), "String"),
+        ]
+    );
+}
+
+#[test]
+fn schema_enum() {
+    derive_enum!(
+        #[yuuka(schema)]
+        enum Member {
+            #[doc = "A student"]
+            Student {
+                name: String,
+                club?: enum Club {
+                    Seminar,
+                    GameDevelopment,
+                },
+            },
+            Teacher(String, u32),
+            Nobody,
+        } = Nobody
+    );
+
+    let TypeSchema::Enum(schema) = Member::schema() else {
+        panic!("expected an enum schema");
+    };
+    assert_eq!(schema.name, "Member");
+    assert_eq!(schema.default, Some("Self::Nobody"));
+    assert_eq!(schema.variants.len(), 3);
+
+    let student = &schema.variants[0];
+    assert_eq!(student.attrs, &[r#"doc = "A student""#]);
+    let VariantShape::Struct(fields) = &student.shape else {
+        panic!("expected a struct variant");
+    };
+    assert_eq!(fields[1].ty, "Option<Club>");
+    let TypeRef::Generated(club) = fields[1].inner else {
+        panic!("expected a generated type");
+    };
+    let TypeSchema::Enum(club) = club() else {
+        panic!("expected an enum schema");
+    };
+    assert!(!club.anonymous);
+    assert_eq!(club.variants[1].name, "GameDevelopment");
+
+    let VariantShape::Tuple(items) = &schema.variants[1].shape else {
+        panic!("expected a tuple variant");
+    };
+    assert_eq!(
+        items.iter().map(|item| item.name).collect::<Vec<_>>(),
+        vec!["0", "1"]
+    );
+    assert!(matches!(schema.variants[2].shape, VariantShape::Unit));
+}
+
+#[test]
+fn schema_source_text() {
+    // Types and defaults are written like in `yuuka_core::ir`.
+    derive_struct!(
+        #[yuuka(schema)]
+        Layout {
+            offset: i32 = -1,
+            sizes: HashMap<String, Vec<u8>> = HashMap::from([("a".to_string(), vec![1, 2])]),
+            anchor: Option<&'static str> = None,
+        }
+    );
+
+    let TypeSchema::Struct(schema) = Layout::schema() else {
+        panic!("expected a struct schema");
+    };
+    let texts = schema
+        .fields
+        .iter()
+        .map(|field| (field.ty, field.default.unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        texts,
+        vec![
+            ("i32", "-1"),
+            (
+                "HashMap<String, Vec<u8>>",
+                r#"HashMap::from([("a".to_string(), vec![1, 2])])"#
+            ),
+            ("Option<&'static str>", "None"),
+        ]
+    );
+}
+
+#[test]
+fn schema_variant_field_defaults() {
+    // Defaults of struct variant fields are not used, so serde requires them.
+    derive_enum!(
+        #[derive(Serialize, Deserialize)]
+        #[yuuka(serde_default, schema)]
+        enum Command {
+            Move { x: i32 = 5, y: i32 },
+            Stop,
+        } = Stop
+    );
+
+    let TypeSchema::Enum(schema) = Command::schema() else {
+        panic!("expected an enum schema");
+    };
+    let VariantShape::Struct(fields) = &schema.variants[0].shape else {
+        panic!("expected a struct variant");
+    };
+    assert_eq!(fields[0].default, None);
+    assert!(!fields[0].serde_default);
+    assert!(serde_json::from_str::<Command>(r#"{"Move":{"y":1}}"#).is_err());
+    assert_eq!(
+        yuuka::json_schema::<Command>()["oneOf"][0]["properties"]["Move"]["required"],
+        serde_json::json!(["x", "y"])
+    );
+}
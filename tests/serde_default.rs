@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use yuuka::{derive_enum, derive_struct};
+
+#[test]
+fn serde_default_root() {
+    derive_struct!(
+        #[derive(PartialEq, Serialize, Deserialize)]
+        #[yuuka(serde_default)]
+        Root {
+            name: String = "yuuka".to_string(),
+            r#type: String = "student".to_string(),
+            tags: Vec<String> = vec!["math".to_string(), "seminar".to_string()],
+            age: u32,
+        }
+    );
+
+    let ret: Root = serde_json::from_str(r#"{"age":16}"#).unwrap();
+    assert_eq!(
+        ret,
+        Root {
+            name: "yuuka".to_string(),
+            r#type: "student".to_string(),
+            tags: vec!["math".to_string(), "seminar".to_string()],
+            age: 16,
+        }
+    );
+
+    // Fields without a DSL default are still required.
+    assert!(serde_json::from_str::<Root>(r#"{}"#).is_err());
+}
+
+#[test]
+fn serde_default_nested() {
+    derive_struct!(
+        #[derive(PartialEq, Serialize, Deserialize)]
+        #[yuuka(serde_default)]
+        Root {
+            info: {
+                first_name: String = "Hayase".to_string(),
+                last_name: String = "Yuuka".to_string(),
+            },
+            mode: enum Mode {
+                Direct,
+                Proxy,
+            } = Proxy,
+            members: [Member {
+                name: String = "noa".to_string(),
+                age?: u32,
+            }],
+        }
+    );
+
+    let ret: Root = serde_json::from_str(r#"{"info":{"first_name":"Kurosaki"}}"#).unwrap();
+    assert_eq!(ret.info.first_name, "Kurosaki");
+    assert_eq!(ret.info.last_name, "Yuuka");
+    assert_eq!(ret.mode, Mode::Proxy);
+    assert!(ret.members.is_empty());
+
+    let ret: Root = serde_json::from_str(r#"{"members":[{}]}"#).unwrap();
+    assert_eq!(ret.info, Root::default().info);
+    assert_eq!(
+        ret.members,
+        vec![Member {
+            name: "noa".to_string(),
+            age: None,
+        }]
+    );
+}
+
+#[test]
+fn serde_default_on_field() {
+    derive_struct!(
+        #[derive(PartialEq, Serialize, Deserialize)]
+        Root {
+            name: String = "yuuka".to_string(),
+            #[yuuka(serde_default)]
+            info: {
+                club: String = "seminar".to_string(),
+            },
+        }
+    );
+
+    let ret: Root = serde_json::from_str(r#"{"name":"noa","info":{}}"#).unwrap();
+    assert_eq!(ret.info.club, "seminar");
+    assert!(serde_json::from_str::<Root>(r#"{"info":{}}"#).is_err());
+}
+
+#[test]
+fn serde_default_enum_root() {
+    derive_enum!(
+        #[derive(PartialEq, Serialize, Deserialize)]
+        #[yuuka(serde_default)]
+        enum Member {
+            Student(Student {
+                name: String = "yuuka".to_string(),
+            }),
+            Teacher,
+        } = Teacher
+    );
+
+    assert_eq!(
+        serde_json::from_str::<Member>(r#"{"Student":{}}"#).unwrap(),
+        Member::Student(Student {
+            name: "yuuka".to_string(),
+        })
+    );
+}
+
+#[test]
+fn serde_default_inline_enum_without_default() {
+    // An inline enum without `= Variant` does not implement `Default`, so it
+    // stays required, while the wrappers around one can still be left out.
+    derive_enum!(
+        #[derive(PartialEq, Serialize, Deserialize)]
+        #[yuuka(serde_default, schema)]
+        enum Shape {
+            Circle {
+                fill: enum Fill { Solid, Hollow },
+                stroke?: enum Stroke { Dashed, Dotted },
+                marks: [enum Mark { Dot, Cross }],
+                corner: enum Corner { Round, Square } = Round,
+            },
+            Empty,
+        } = Empty
+    );
+
+    assert_eq!(
+        serde_json::from_str::<Shape>(r#"{"Circle":{"fill":"Hollow"}}"#).unwrap(),
+        Shape::Circle {
+            fill: Fill::Hollow,
+            stroke: None,
+            marks: vec![],
+            corner: Corner::Round,
+        }
+    );
+    assert!(serde_json::from_str::<Shape>(r#"{"Circle":{}}"#).is_err());
+
+    let yuuka::schema::TypeSchema::Enum(schema) = <Shape as yuuka::Schema>::schema() else {
+        unreachable!()
+    };
+    let yuuka::schema::VariantShape::Struct(fields) = schema.variants[0].shape else {
+        unreachable!()
+    };
+    assert_eq!(
+        fields
+            .iter()
+            .map(|field| (field.name, field.serde_default))
+            .collect::<Vec<_>>(),
+        [
+            ("fill", false),
+            ("stroke", true),
+            ("marks", true),
+            ("corner", true),
+        ]
+    );
+}
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use yuuka::{derive_enum, derive_struct};
+
+#[test]
+fn skip_none() {
+    derive_struct!(
+        #[derive(Serialize, Deserialize)]
+        #[yuuka(skip_none)]
+        Root {
+            name: String,
+            nick_name?: String,
+            info?: {
+                age?: u32,
+                club: String,
+            },
+            members?: [Member {
+                name: String,
+            }],
+        }
+    );
+
+    let ret = Root {
+        name: "yuuka".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(serde_json::to_string(&ret).unwrap(), r#"{"name":"yuuka"}"#);
+
+    let ret = Root {
+        info: Some(Default::default()),
+        ..ret
+    };
+    assert_eq!(
+        serde_json::to_string(&ret).unwrap(),
+        r#"{"name":"yuuka","info":{"club":""}}"#
+    );
+}
+
+#[test]
+fn skip_none_in_enum_struct_variant() {
+    derive_enum!(
+        #[derive(Serialize, Deserialize)]
+        #[yuuka(skip_none)]
+        enum Member {
+            Student {
+                name: String,
+                club?: String,
+            },
+            Teacher,
+        } = Teacher
+    );
+
+    assert_eq!(
+        serde_json::to_string(&Member::Student {
+            name: "yuuka".to_string(),
+            club: None,
+        })
+        .unwrap(),
+        r#"{"Student":{"name":"yuuka"}}"#
+    );
+}
+
+#[test]
+fn skip_empty() {
+    derive_struct!(
+        #[derive(Serialize, Deserialize)]
+        #[yuuka(skip_empty)]
+        Root {
+            name: String,
+            members: [Member {
+                name: String,
+                roles: [enum Role {
+                    Leader,
+                    Member,
+                } = Member],
+            }],
+        }
+    );
+
+    let ret = Root {
+        name: "seminar".to_string(),
+        members: vec![Member {
+            name: "yuuka".to_string(),
+            roles: vec![],
+        }],
+    };
+    assert_eq!(
+        serde_json::to_string(&ret).unwrap(),
+        r#"{"name":"seminar","members":[{"name":"yuuka"}]}"#
+    );
+    assert_eq!(
+        serde_json::to_string(&Root {
+            members: vec![],
+            ..ret
+        })
+        .unwrap(),
+        r#"{"name":"seminar"}"#
+    );
+}
+
+#[test]
+fn skip_default() {
+    derive_struct!(
+        #[derive(PartialEq, Serialize, Deserialize)]
+        #[yuuka(skip_default, skip_none, serde_default)]
+        Root {
+            name: String = "yuuka".to_string(),
+            age: u32 = 16,
+            club?: String = Some("seminar".to_string()),
+            nick_name?: String,
+            info: {
+                height: u32 = 156,
+                weight?: u32,
+            },
+        }
+    );
+
+    assert_eq!(
+        serde_json::to_string(&Root::default()).unwrap(),
+        r#"{"info":{}}"#
+    );
+
+    let ret = Root {
+        age: 17,
+        club: None,
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&ret).unwrap();
+    assert_eq!(json, r#"{"age":17,"club":null,"info":{}}"#);
+    assert_eq!(serde_json::from_str::<Root>(&json).unwrap(), ret);
+}
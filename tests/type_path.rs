@@ -0,0 +1,89 @@
+use yuuka::{derive_enum, derive_struct, ty};
+
+#[test]
+fn type_path_struct() {
+    derive_struct!(
+        #[derive(PartialEq)]
+        Root {
+            nick_name: {
+                chinese: {
+                    first_name: String = "早濑".to_string(),
+                    last_name: String = "优香".to_string(),
+                },
+                japanese: Japanese {
+                    first_name: String = "早瀬".to_string(),
+                    last_name: String = "ユウカ".to_string(),
+                },
+            },
+            tags: [{
+                name: String,
+            }],
+            location?: {
+                country: String,
+            },
+            level: u32,
+        }
+    );
+
+    impl ty!(Root.nick_name.chinese) {
+        fn full_name(&self) -> String {
+            format!("{}{}", self.first_name, self.last_name)
+        }
+    }
+
+    fn tag(name: &str) -> ty!(Root.tags) {
+        <ty!(Root.tags)>::default().with_name(name)
+    }
+
+    impl ty!(Root.tags) {
+        fn with_name(mut self, name: &str) -> Self {
+            self.name = name.to_string();
+            self
+        }
+    }
+
+    let root = Root::default();
+    assert_eq!(root.nick_name.chinese.full_name(), "早濑优香");
+    assert_eq!(tag("seminar").name, "seminar");
+
+    let _: ty!(Root) = root.clone();
+    let _: ty!(Root.nick_name.japanese) = Japanese::default();
+    let _: ty!(Root.location) = Default::default();
+    let level: ty!(Root.level) = 1;
+    assert_eq!(level, 1u32);
+}
+
+#[test]
+fn type_path_enum() {
+    derive_enum!(
+        #[derive(PartialEq)]
+        enum Group {
+            Millennium(enum {
+                GameDevelopment(enum {
+                    Momoi,
+                    Midori,
+                    Yuzu,
+                    Arisu,
+                } = Yuzu),
+                Seminar {
+                    members: [{
+                        name: String,
+                    }],
+                },
+            } = GameDevelopment(Default::default())),
+            Gehenna(String, enum {
+                Hina,
+                Ako,
+            } = Hina),
+        } = Millennium(Default::default())
+    );
+
+    let member: ty!(Group.Millennium.0.GameDevelopment.0) = Default::default();
+    assert_eq!(member, <ty!(Group.Millennium.GameDevelopment)>::Yuzu);
+
+    let seminar: ty!(Group.Millennium.Seminar.members) = Default::default();
+    assert_eq!(seminar.name, String::new());
+
+    let prefect: ty!(Group.Gehenna.1) = Default::default();
+    assert_eq!(prefect, <ty!(Group.Gehenna.1)>::Hina);
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use yuuka::{derive_enum, derive_struct, typescript};
+
+derive_struct!(
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    #[yuuka(schema)]
+    Config {
+        listen_port: u16,
+        server_name?: String,
+        #[serde(skip)]
+        secret: String,
+        services: [Service {
+            domain: Vec<String>,
+            rules: [Rule {
+                pattern: String,
+                method: enum Method {
+                    Redirect { url: String },
+                    Proxy { host: String },
+                    StaticDir(String),
+                    Disabled,
+                } = Disabled,
+            }],
+        }],
+    }
+);
+
+#[test]
+fn typescript_struct() {
+    assert_eq!(
+        typescript::<Config>(),
+        r#"export interface Config {
+  listenPort: number;
+  serverName?: string | null;
+  services: Service[];
+}
+
+export interface Service {
+  domain: string[];
+  rules: Rule[];
+}
+
+export interface Rule {
+  pattern: string;
+  method: Method;
+}
+
+export type Method =
+  | { Redirect: { url: string } }
+  | { Proxy: { host: string } }
+  | { StaticDir: string }
+  | "Disabled";
+"#
+    );
+}
+
+#[test]
+fn typescript_rename_all() {
+    derive_enum!(
+        #[derive(Serialize, Deserialize)]
+        #[serde(rename_all = "kebab-case", rename_all_fields = "camelCase")]
+        #[yuuka(schema)]
+        enum Event {
+            ServerStarted { listen_port: u16 },
+            Stopped(i32, Option<String>),
+        }
+    );
+
+    assert_eq!(
+        typescript::<Event>(),
+        r#"export type Event =
+  | { "server-started": { listenPort: number } }
+  | { stopped: [number, string | null] };
+"#
+    );
+}
+
+#[test]
+fn typescript_tagged() {
+    derive_enum!(
+        #[derive(Serialize, Deserialize)]
+        #[serde(tag = "type")]
+        #[yuuka(schema)]
+        enum Internal {
+            Empty,
+            Point { x: f64, y: f64 },
+        }
+    );
+    derive_enum!(
+        #[derive(Serialize, Deserialize)]
+        #[serde(tag = "t", content = "c")]
+        #[yuuka(schema)]
+        enum Adjacent {
+            Empty,
+            Items(Vec<String>),
+        }
+    );
+
+    assert_eq!(
+        typescript::<Internal>(),
+        r#"export type Internal =
+  | { type: "Empty" }
+  | { type: "Point"; x: number; y: number };
+"#
+    );
+    assert_eq!(
+        typescript::<Adjacent>(),
+        r#"export type Adjacent =
+  | { t: "Empty" }
+  | { t: "Items"; c: string[] };
+"#
+    );
+}
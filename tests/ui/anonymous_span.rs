@@ -0,0 +1,12 @@
+use yuuka::derive_struct;
+
+// The error on an anonymous type points at its inline definition.
+derive_struct!(
+    #[yuuka(anonymous_naming = path)]
+    Root {
+        server: { host: String },
+        backup: Root_Server { host: String },
+    }
+);
+
+fn main() {}
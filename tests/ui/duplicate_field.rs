@@ -0,0 +1,10 @@
+use yuuka::derive_struct;
+
+// A field defined twice is reported at both places.
+derive_struct!(Root {
+    name: String,
+    port: u16,
+    name: String,
+});
+
+fn main() {}
@@ -0,0 +1,16 @@
+use yuuka::derive_struct;
+
+// Named inline types share one module, so the same name in different
+// branches is reported at both definitions.
+derive_struct!(Root {
+    server: Server {
+        host: String,
+    },
+    backup: {
+        server: Server {
+            port: u16,
+        },
+    },
+});
+
+fn main() {}
@@ -0,0 +1,12 @@
+use yuuka::derive_enum;
+
+// A variant defined twice is reported at both places.
+derive_enum!(
+    enum Mode {
+        Dev,
+        Prod(String),
+        Dev,
+    }
+);
+
+fn main() {}
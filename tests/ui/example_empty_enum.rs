@@ -0,0 +1,9 @@
+use yuuka::derive_enum;
+
+// There is no value to return from `example()` without a variant.
+derive_enum!(
+    #[yuuka(example)]
+    enum Never {}
+);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use yuuka::derive_struct_file;
+
+// Errors in the file are reported with their position in it. trybuild builds
+// the test from `target/tests/trybuild/yuuka`, hence the path.
+derive_struct_file!("../../../../tests/ui/file_error.yuuka");
+
+fn main() {}
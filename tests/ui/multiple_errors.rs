@@ -0,0 +1,14 @@
+use yuuka::derive_struct;
+
+// Both collisions are reported, not only the first one.
+derive_struct!(
+    #[yuuka(anonymous_naming = path_camel)]
+    Root {
+        a_b: { x: u8 },
+        a: { b: { y: u8 } },
+        c_d: { x: u8 },
+        c: { d: { y: u8 } },
+    }
+);
+
+fn main() {}
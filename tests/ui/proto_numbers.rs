@@ -0,0 +1,14 @@
+use yuuka::derive_struct;
+
+// Positional numbers would shift as fields are added, so they cannot be mixed
+// with explicit ones.
+derive_struct!(
+    #[yuuka(schema)]
+    Root {
+        #[yuuka(proto = 1)]
+        a: u8,
+        b: u8,
+    }
+);
+
+fn main() {}
@@ -0,0 +1,24 @@
+use yuuka::derive_enum;
+
+// Variants are numbered like fields, and the values of a unit-only enum
+// start from 0.
+derive_enum!(
+    #[yuuka(schema)]
+    enum Level {
+        #[yuuka(proto = 1)]
+        Low,
+        High,
+    }
+);
+
+derive_enum!(
+    #[yuuka(schema)]
+    enum Method {
+        #[yuuka(proto = 0)]
+        Static(String),
+        #[yuuka(proto = 2)]
+        Disabled,
+    }
+);
+
+fn main() {}
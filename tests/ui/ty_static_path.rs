@@ -0,0 +1,17 @@
+use yuuka::{derive_struct, ty};
+
+// Paths only walk into inline types, so they end at types written in the DSL.
+derive_struct!(Root {
+    name: String,
+    inner: {
+        value: i32,
+    },
+});
+
+fn value(value: ty!(Root.inner.value)) -> i32 {
+    value
+}
+
+fn name_len(_: ty!(Root.name.len)) {}
+
+fn main() {}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use yuuka::{derive_enum, derive_struct};
+
+#[test]
+fn yuuka_attribute_order_independent() {
+    derive_struct!(
+        #[serde(rename_all = "camelCase")]
+        #[yuuka(derive(Serialize, Deserialize, PartialEq))]
+        Root {
+            nick_name: String,
+            live_in: String,
+        }
+    );
+
+    let ret = Root {
+        nick_name: "langyo".to_string(),
+        live_in: "China".to_string(),
+    };
+    assert_eq!(
+        serde_json::to_string(&ret).unwrap(),
+        r#"{"nickName":"langyo","liveIn":"China"}"#
+    );
+}
+
+#[test]
+fn yuuka_attribute_recursive() {
+    derive_struct!(
+        #[yuuka(recursive(serde(rename_all = "camelCase")))]
+        #[derive(Serialize, Deserialize)]
+        Root {
+            nick_name: {
+                first_name: String = "Hayase".to_string(),
+                last_name: String = "Yuuka".to_string(),
+            },
+        }
+    );
+
+    assert_eq!(
+        serde_json::to_string(&Root::default()).unwrap(),
+        r#"{"nickName":{"firstName":"Hayase","lastName":"Yuuka"}}"#
+    );
+}
+
+#[test]
+fn yuuka_attribute_on_inline_types() {
+    derive_struct!(
+        #[yuuka(derive(Serialize, Deserialize))]
+        #[serde(deny_unknown_fields)]
+        Root {
+            nick_name: String,
+            #[yuuka(attr(serde(rename_all = "UPPERCASE")), derive(PartialEq))]
+            #[serde(rename = "position")]
+            location: {
+                country: String = "kivotos".to_string(),
+                address: String = "777".to_string(),
+            },
+        }
+    );
+
+    assert_eq!(
+        serde_json::to_string(&Root {
+            nick_name: "arisu".to_string(),
+            location: Default::default(),
+        })
+        .unwrap(),
+        r#"{"nick_name":"arisu","position":{"COUNTRY":"kivotos","ADDRESS":"777"}}"#
+    );
+}
+
+#[test]
+fn yuuka_attribute_enum() {
+    derive_enum!(
+        #[yuuka(derive(Serialize, Deserialize), anonymous_naming = path)]
+        #[serde(rename_all = "snake_case")]
+        enum Group {
+            #[serde(rename = "777")]
+            #[yuuka(attr(serde(rename_all = "UPPERCASE")))]
+            Millennium(enum {
+                GameDevelopment,
+                Seminar,
+            }),
+            HanaokaYuzu,
+        } = HanaokaYuzu
+    );
+
+    assert_eq!(
+        serde_json::to_string(&Group::default()).unwrap(),
+        r#""hanaoka_yuzu""#
+    );
+    assert_eq!(
+        serde_json::to_string(&Group::Millennium(Group_Millennium::GameDevelopment)).unwrap(),
+        r#"{"777":"GAMEDEVELOPMENT"}"#
+    );
+}